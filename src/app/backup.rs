@@ -0,0 +1,57 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+pub const BITMAP_NAME: &str = "mima-backup";
+pub const ID_FORMAT: &str = "[year][month][day]-[hour][minute][second]";
+
+pub fn guest_path<T, U>(path: T, guest_id: U) -> PathBuf
+where
+    T: AsRef<Path>,
+    U: AsRef<str>,
+{
+    path.as_ref().join(guest_id.as_ref())
+}
+
+pub fn backup_path<T, U, V>(path: T, guest_id: U, backup_id: V) -> PathBuf
+where
+    T: AsRef<Path>,
+    U: AsRef<str>,
+    V: AsRef<str>,
+{
+    guest_path(path, guest_id).join(backup_id.as_ref())
+}
+
+pub fn disk_path<T, U>(backup_path: T, label: U) -> PathBuf
+where
+    T: AsRef<Path>,
+    U: AsRef<str>,
+{
+    backup_path
+        .as_ref()
+        .join(format!("{label}.qcow2", label = label.as_ref()))
+}
+
+// the nvram sits in its own directory, so it never clashes with a disk label
+pub fn nvram_path<T>(backup_path: T) -> PathBuf
+where
+    T: AsRef<Path>,
+{
+    backup_path.as_ref().join("firmware").join("nvram.qcow2")
+}
+
+// backing files are referenced relative to the backup, so chains stay intact
+// when the whole backup directory is moved
+pub fn backing_path<T, U>(backup_id: T, label: U) -> PathBuf
+where
+    T: AsRef<str>,
+    U: AsRef<str>,
+{
+    disk_path(Path::new("..").join(backup_id.as_ref()), label)
+}
+
+pub fn job_id<T>(label: T) -> String
+where
+    T: AsRef<str>,
+{
+    format!("mima-backup-{label}", label = label.as_ref())
+}
@@ -0,0 +1,55 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+pub const GUEST_MEMBER: &str = "guest.toml";
+pub const MANIFEST_MEMBER: &str = "manifest.json";
+pub const NVRAM_MEMBER: &str = "nvram.qcow2";
+
+#[derive(Deserialize, Serialize)]
+pub struct Manifest {
+    pub guest_id: String,
+    pub snapshots: Vec<ManifestSnapshot>,
+    #[serde(default)]
+    pub nvram: bool,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct ManifestSnapshot {
+    pub id: String,
+    pub timestamp: u64,
+}
+
+pub fn disk_member<T>(label: T) -> String
+where
+    T: AsRef<str>,
+{
+    format!("disks/{label}.qcow2", label = label.as_ref())
+}
+
+// tar renames members through a sed expression, which paths must neither end
+// early nor extend with patterns or back references, dots are left alone as
+// they only ever match the member they stand for
+pub fn rename_transform<T, U>(from: T, to: U) -> String
+where
+    T: AsRef<str>,
+    U: AsRef<str>,
+{
+    let mut pattern = String::new();
+    for character in from.as_ref().chars() {
+        match character {
+            '*' | '[' => pattern.push_str(&format!("[{character}]")),
+            '\\' | ',' => pattern.push_str(&format!("\\{character}")),
+            _ => pattern.push(character),
+        }
+    }
+
+    let mut replacement = String::new();
+    for character in to.as_ref().chars() {
+        if matches!(character, '\\' | ',' | '&') {
+            replacement.push('\\');
+        }
+        replacement.push(character);
+    }
+
+    format!("--transform=s,^{pattern}$,{replacement},")
+}
@@ -1,3 +1,4 @@
+use crate::app::backup;
 use crate::app::QEMU_IMG_COMMAND;
 use crate::command::Execute;
 use crate::errors::UnknownSnapshotError;
@@ -15,17 +16,41 @@ impl App {
         let guest_id = guest_id.as_ref();
         let snapshot_id = snapshot_id.as_ref();
 
-        let snapshots = self.get_guest_snapshots(&connection, guest_id)?;
+        let snapshots = self.get_guest_snapshots(connection, guest_id)?;
         if !snapshots.contains_key(snapshot_id) {
             anyhow::bail!(UnknownSnapshotError::new(guest_id, snapshot_id));
         }
 
         let disks = self.get_guest_disks(guest_id)?;
-        for disk in disks {
+        for (disk_id, disk) in disks.iter().enumerate() {
             command_macros::command! {
                 {connection.execute(QEMU_IMG_COMMAND)} snapshot -a(snapshot_id) (disk.path)
             }
             .execute()?;
+
+            // reverting bypasses dirty tracking, so the next backup has to be a full one
+            let info = self.get_disk_info(connection, guest_id, disk_id)?;
+            if info
+                .bitmaps
+                .iter()
+                .any(|bitmap| bitmap == backup::BITMAP_NAME)
+            {
+                command_macros::command! {
+                    {connection.execute(QEMU_IMG_COMMAND)} bitmap --remove (disk.path) (backup::BITMAP_NAME)
+                }
+                .execute()?;
+            }
+        }
+
+        // guests switched to uefi later lack the older snapshots in nvram
+        if let Some(nvram_path) = self.get_existing_guest_nvram_path(connection, guest_id)? {
+            let info = self.get_image_info(connection, &nvram_path)?;
+            if info.snapshots.contains_key(snapshot_id) {
+                command_macros::command! {
+                    {connection.execute(QEMU_IMG_COMMAND)} snapshot -a(snapshot_id) (nvram_path)
+                }
+                .execute()?;
+            }
         }
 
         Ok(())
@@ -0,0 +1,459 @@
+use crate::app::backup;
+use crate::app::SshConnection;
+use crate::app::CHOWN_COMMAND;
+use crate::app::QEMU_IMG_COMMAND;
+use crate::app::QEMU_USER;
+use crate::app::RM_COMMAND;
+use crate::command::Execute;
+use crate::errors::BackupFailedError;
+use crate::errors::MissingBaseBackupError;
+use crate::errors::MissingQmpSocketError;
+use crate::App;
+use anyhow::Result;
+use colored::*;
+use serde::de::IgnoredAny;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::Duration;
+use time::OffsetDateTime;
+
+const THAW_ATTEMPTS: u32 = 3;
+
+impl App {
+    pub fn backup_guest<T, U>(
+        &self,
+        guest_id: T,
+        path: U,
+        incremental: bool,
+        keep: Option<usize>,
+    ) -> Result<()>
+    where
+        T: AsRef<str>,
+        U: AsRef<Path>,
+    {
+        let connection = self.get_host_ssh_connection();
+
+        let guest_id = guest_id.as_ref();
+        let path = path.as_ref();
+
+        let guest = self.get_guest(guest_id)?;
+
+        let base_backup_id = if incremental {
+            let backups = self.get_guest_backups(connection, guest_id, path)?;
+            match backups.last() {
+                Some(backup_id) => Some(backup_id.clone()),
+                None => anyhow::bail!(MissingBaseBackupError::new(guest_id)),
+            }
+        } else {
+            None
+        };
+
+        let format = time::format_description::parse(backup::ID_FORMAT)?;
+        let backup_id = OffsetDateTime::now_utc().format(&format)?;
+        let backup_path = backup::backup_path(path, guest_id, &backup_id);
+
+        // only qmp reports whether the backup jobs of a running guest succeeded
+        let booted = self.is_booted(connection, guest_id)?;
+        if booted && guest.qmp_socket_path.is_none() {
+            anyhow::bail!(MissingQmpSocketError::new(guest_id));
+        }
+
+        // a failed backup must not be picked up as the base of the next one
+        let result =
+            self.create_backup(connection, guest_id, booted, &backup_path, &base_backup_id);
+        if let Err(error) = result {
+            let _ = command_macros::command! {
+                {connection.execute(RM_COMMAND)} -rf (backup_path)
+            }
+            .execute();
+            return Err(error);
+        }
+
+        // bitmaps are only reset once the backup passed its check, otherwise
+        // the next incremental backup would skip the writes it missed, and
+        // bitmaps that fail to reset merely make it copy more
+        if !booted {
+            self.reset_stopped_guest_bitmaps(connection, guest_id)?;
+        }
+
+        println!("{backup_id}");
+
+        if let Some(keep) = keep {
+            let backups = self.get_guest_backups(connection, guest_id, path)?;
+            let mut count = backups.len().saturating_sub(keep);
+            // incremental backups are useless without the backups they are based on
+            while count > 0
+                && count < backups.len()
+                && self.is_incremental_backup(connection, guest_id, path, &backups[count])?
+            {
+                count -= 1;
+            }
+            for backup_id in &backups[..count] {
+                command_macros::command! {
+                    {connection.execute(RM_COMMAND)} -r (backup::backup_path(path, guest_id, backup_id))
+                }
+                .execute()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn create_backup(
+        &self,
+        connection: &SshConnection,
+        guest_id: &str,
+        booted: bool,
+        backup_path: &Path,
+        base_backup_id: &Option<String>,
+    ) -> Result<()> {
+        if booted {
+            self.backup_booted_guest(connection, guest_id, backup_path, base_backup_id)?;
+        } else {
+            self.backup_stopped_guest(connection, guest_id, backup_path, base_backup_id)?;
+        }
+
+        // the nvram is small and rarely written, so it is copied in full every time,
+        // sharing the image with qemu while the guest runs
+        if let Some(nvram_path) = self.get_existing_guest_nvram_path(connection, guest_id)? {
+            let target_path = backup::nvram_path(backup_path);
+
+            self.create_parent_dir(connection, &target_path)?;
+            command_macros::command! {
+                {connection.execute(QEMU_IMG_COMMAND)} convert -q if booted { -U } -O qcow2 (nvram_path) (target_path)
+            }
+            .execute()?;
+        }
+
+        // checking the image also opens its backing chain, so broken
+        // incremental chains are caught right away
+        for disk in self.get_guest_disks(guest_id)? {
+            command_macros::command! {
+                {connection.execute(QEMU_IMG_COMMAND)} check -q (backup::disk_path(backup_path, &disk.label))
+            }
+            .execute()?;
+        }
+
+        Ok(())
+    }
+
+    fn backup_stopped_guest(
+        &self,
+        connection: &SshConnection,
+        guest_id: &str,
+        backup_path: &Path,
+        base_backup_id: &Option<String>,
+    ) -> Result<()> {
+        let disks = self.get_guest_disks(guest_id)?;
+
+        for disk in disks {
+            let target_path = backup::disk_path(backup_path, &disk.label);
+
+            // without a running qemu there is no dirty tracking, so incremental
+            // backups only write clusters that differ from the base backup
+            self.create_parent_dir(connection, &target_path)?;
+            command_macros::command! {
+                {connection.execute(QEMU_IMG_COMMAND)} convert -q -O qcow2
+                if let Some(base_backup_id) = base_backup_id {
+                    -B (backup::backing_path(base_backup_id, &disk.label)) -F qcow2
+                }
+                (disk.path) (target_path)
+            }
+            .execute()?;
+        }
+
+        Ok(())
+    }
+
+    fn reset_stopped_guest_bitmaps(
+        &self,
+        connection: &SshConnection,
+        guest_id: &str,
+    ) -> Result<()> {
+        let disks = self.get_guest_disks(guest_id)?;
+
+        for (disk_id, disk) in disks.iter().enumerate() {
+            let info = self.get_disk_info(connection, guest_id, disk_id)?;
+            if info
+                .bitmaps
+                .iter()
+                .any(|bitmap| bitmap == backup::BITMAP_NAME)
+            {
+                command_macros::command! {
+                    {connection.execute(QEMU_IMG_COMMAND)} bitmap --clear (disk.path) (backup::BITMAP_NAME)
+                }
+                .execute()?;
+            } else {
+                command_macros::command! {
+                    {connection.execute(QEMU_IMG_COMMAND)} bitmap --add (disk.path) (backup::BITMAP_NAME)
+                }
+                .execute()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn backup_booted_guest(
+        &self,
+        connection: &SshConnection,
+        guest_id: &str,
+        backup_path: &Path,
+        base_backup_id: &Option<String>,
+    ) -> Result<()> {
+        let disks = self.get_guest_disks(guest_id)?;
+
+        #[derive(Deserialize)]
+        struct BlockInfo {
+            device: String,
+            inserted: Option<BlockDeviceInfo>,
+        }
+
+        #[derive(Deserialize)]
+        struct BlockDeviceInfo {
+            #[serde(default, rename = "dirty-bitmaps")]
+            dirty_bitmaps: Vec<DirtyBitmapInfo>,
+        }
+
+        #[derive(Deserialize)]
+        struct DirtyBitmapInfo {
+            name: String,
+        }
+
+        // only full backups have to know whether bitmaps are already there
+        let mut devices_with_bitmap = HashSet::new();
+        if base_backup_id.is_none() {
+            let blocks: Vec<BlockInfo> = self.execute_qmp_command(
+                connection,
+                guest_id,
+                "query-block",
+                serde_json::json!({}),
+            )?;
+            for block in blocks {
+                if let Some(inserted) = block.inserted {
+                    if inserted
+                        .dirty_bitmaps
+                        .iter()
+                        .any(|bitmap| bitmap.name == backup::BITMAP_NAME)
+                    {
+                        devices_with_bitmap.insert(block.device);
+                    }
+                }
+            }
+        }
+
+        let mut actions = Vec::new();
+        let mut added_bitmaps = Vec::new();
+        for (disk_id, disk) in disks.iter().enumerate() {
+            let target_path = backup::disk_path(backup_path, &disk.label);
+            let device = format!("drive.{label}", label = disk.label);
+
+            // qemu runs unprivileged, so the target image is created upfront
+            // and handed over to qemu user
+            self.create_parent_dir(connection, &target_path)?;
+            if let Some(base_backup_id) = base_backup_id {
+                command_macros::command! {
+                    {connection.execute(QEMU_IMG_COMMAND)} create -q -fqcow2 -b (backup::backing_path(base_backup_id, &disk.label)) -F qcow2 (target_path)
+                }
+                .execute()?;
+            } else {
+                let info = self.get_disk_info(connection, guest_id, disk_id)?;
+                command_macros::command! {
+                    {connection.execute(QEMU_IMG_COMMAND)} create -q -fqcow2 (target_path) ((info.virtual_size))
+                }
+                .execute()?;
+            }
+            command_macros::command! {
+                {connection.execute(CHOWN_COMMAND)} (QEMU_USER) (target_path)
+            }
+            .execute()?;
+
+            // the bitmap is only reset once every job succeeded, so a failed
+            // backup never breaks the chain of the next incremental one
+            if base_backup_id.is_some() {
+                actions.push(serde_json::json!({
+                    "type": "drive-backup",
+                    "data": {
+                        "job-id": backup::job_id(&disk.label),
+                        "device": device,
+                        "target": target_path,
+                        "format": "qcow2",
+                        "mode": "existing",
+                        "sync": "incremental",
+                        "bitmap": backup::BITMAP_NAME,
+                        "auto-dismiss": false,
+                    },
+                }));
+            } else {
+                if !devices_with_bitmap.contains(&device) {
+                    added_bitmaps.push(device.clone());
+                }
+                actions.push(serde_json::json!({
+                    "type": "drive-backup",
+                    "data": {
+                        "job-id": backup::job_id(&disk.label),
+                        "device": device,
+                        "target": target_path,
+                        "format": "qcow2",
+                        "mode": "existing",
+                        "sync": "full",
+                        "bitmap": backup::BITMAP_NAME,
+                        "bitmap-mode": "on-success",
+                        "auto-dismiss": false,
+                    },
+                }));
+            }
+        }
+
+        // grouped transactions only take backup actions, bitmaps that are
+        // still missing are added right before, writes in between are merely
+        // copied once more by the next incremental backup
+        for device in &added_bitmaps {
+            self.execute_qmp_command::<_, _, IgnoredAny>(
+                connection,
+                guest_id,
+                "block-dirty-bitmap-add",
+                serde_json::json!({ "node": device, "name": backup::BITMAP_NAME, "persistent": true }),
+            )?;
+        }
+
+        let result = self.run_backup_jobs(connection, guest_id, actions);
+        if result.is_err() {
+            // bitmaps that only track writes since this failed backup would
+            // make the next incremental backup skip earlier changes
+            for device in &added_bitmaps {
+                let _ = self.execute_qmp_command::<_, _, IgnoredAny>(
+                    connection,
+                    guest_id,
+                    "block-dirty-bitmap-remove",
+                    serde_json::json!({ "node": device, "name": backup::BITMAP_NAME }),
+                );
+            }
+        }
+
+        result
+    }
+
+    fn run_backup_jobs(
+        &self,
+        connection: &SshConnection,
+        guest_id: &str,
+        actions: Vec<serde_json::Value>,
+    ) -> Result<()> {
+        let disks = self.get_guest_disks(guest_id)?;
+
+        // the jobs copy what the disks held when the transaction started, so
+        // the guest only has to stay frozen until then
+        let frozen = self.freeze_guest_filesystems(connection, guest_id)?;
+        let result = self.execute_qmp_command::<_, _, IgnoredAny>(
+            connection,
+            guest_id,
+            "transaction",
+            serde_json::json!({
+                "actions": actions,
+                // a failing job cancels the others, so either all bitmaps
+                // are reset or none
+                "properties": { "completion-mode": "grouped" },
+            }),
+        );
+        // a failed thaw must not hide why the transaction failed, and the
+        // backup itself is fine, but a guest left frozen blocks every write
+        if frozen {
+            if let Err(error) = self.thaw_guest_filesystems(connection, guest_id) {
+                eprintln!(
+                    "{} {error}, filesystems of guest '{}' are still frozen",
+                    "warning:".yellow().bold(),
+                    guest_id.yellow(),
+                );
+            }
+        }
+        result?;
+
+        #[derive(Deserialize)]
+        struct JobInfo {
+            id: String,
+            status: String,
+            error: Option<String>,
+        }
+
+        let job_ids: HashSet<_> = disks
+            .iter()
+            .map(|disk| backup::job_id(&disk.label))
+            .collect();
+        let delay = Duration::from_millis(1000);
+        let jobs = loop {
+            let jobs: Vec<JobInfo> = self.execute_qmp_command(
+                connection,
+                guest_id,
+                "query-jobs",
+                serde_json::json!({}),
+            )?;
+            let jobs: Vec<_> = jobs
+                .into_iter()
+                .filter(|job| job_ids.contains(&job.id))
+                .collect();
+            if jobs.iter().all(|job| job.status == "concluded") {
+                break jobs;
+            }
+
+            std::thread::sleep(delay);
+        };
+
+        for job in &jobs {
+            self.execute_qmp_command::<_, _, IgnoredAny>(
+                connection,
+                guest_id,
+                "job-dismiss",
+                serde_json::json!({ "id": job.id }),
+            )?;
+        }
+
+        for disk in disks {
+            let job_id = backup::job_id(&disk.label);
+            if let Some(job) = jobs.iter().find(|job| job.id == job_id) {
+                if let Some(error) = &job.error {
+                    anyhow::bail!(BackupFailedError::new(guest_id, &disk.label, error));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn freeze_guest_filesystems(&self, connection: &SshConnection, guest_id: &str) -> Result<bool> {
+        // guests without a running agent are backed up as they are
+        if !self.is_guest_agent_running(connection, guest_id)? {
+            return Ok(false);
+        }
+
+        self.execute_guest_agent_command::<_, _, IgnoredAny>(
+            connection,
+            guest_id,
+            "guest-fsfreeze-freeze",
+            serde_json::json!({}),
+        )?;
+
+        Ok(true)
+    }
+
+    fn thaw_guest_filesystems(&self, connection: &SshConnection, guest_id: &str) -> Result<()> {
+        let delay = Duration::from_millis(1000);
+        let mut attempt = 1;
+        loop {
+            let result = self.execute_guest_agent_command::<_, _, IgnoredAny>(
+                connection,
+                guest_id,
+                "guest-fsfreeze-thaw",
+                serde_json::json!({}),
+            );
+            if result.is_ok() || attempt == THAW_ATTEMPTS {
+                result?;
+                return Ok(());
+            }
+
+            attempt += 1;
+            std::thread::sleep(delay);
+        }
+    }
+}
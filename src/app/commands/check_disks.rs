@@ -0,0 +1,79 @@
+use crate::app::QEMU_IMG_COMMAND;
+use crate::command::Execute;
+use crate::errors::BootedGuestError;
+use crate::App;
+use anyhow::Result;
+use serde::Deserialize;
+use std::io::Write;
+use tabwriter::TabWriter;
+
+impl App {
+    pub fn check_disks<T>(&self, guest_id: T, repair: Option<String>) -> Result<()>
+    where
+        T: AsRef<str>,
+    {
+        let connection = self.get_host_ssh_connection();
+
+        let guest_id = guest_id.as_ref();
+
+        let disks = self.get_guest_disks(guest_id)?;
+
+        if repair.is_some() && self.is_booted(connection, guest_id)? {
+            anyhow::bail!(BootedGuestError::new(guest_id));
+        }
+
+        #[derive(Deserialize)]
+        struct QemuImgCheck {
+            #[serde(default)]
+            corruptions: u64,
+            #[serde(default)]
+            leaks: u64,
+            #[serde(default, rename = "corruptions-fixed")]
+            corruptions_fixed: u64,
+            #[serde(default, rename = "leaks-fixed")]
+            leaks_fixed: u64,
+            #[serde(rename = "image-end-offset")]
+            image_end_offset: Option<u64>,
+        }
+
+        let mut tw = TabWriter::new(std::io::stdout());
+        writeln!(
+            tw,
+            "LABEL\tCORRUPTIONS\tLEAKS\tCORRUPTIONS FIXED\tLEAKS FIXED\tIMAGE END OFFSET"
+        )
+        .unwrap();
+        for disk in disks {
+            // qemu-img check exits with 2 when corruptions are found and with 3 when only leaks
+            // are found, the report is printed in both cases
+            let check = command_macros::command! {
+                {connection.execute(QEMU_IMG_COMMAND)} check --output=json
+                if let Some(mode) = &repair {
+                    -r (mode)
+                } else {
+                    --force-share
+                }
+                (disk.path)
+            }
+            .execute_with_exit_codes_and_parse_json_output::<QemuImgCheck>(&[0, 2, 3])?;
+
+            let image_end_offset = match check.image_end_offset {
+                Some(offset) => offset.to_string(),
+                None => "-".to_string(),
+            };
+
+            writeln!(
+                tw,
+                "{label}\t{corruptions}\t{leaks}\t{corruptions_fixed}\t{leaks_fixed}\t{image_end_offset}",
+                corruptions = check.corruptions,
+                corruptions_fixed = check.corruptions_fixed,
+                label = disk.label,
+                leaks = check.leaks,
+                leaks_fixed = check.leaks_fixed,
+            )
+            .unwrap();
+        }
+        tw.flush().unwrap();
+
+        Ok(())
+    }
+}
@@ -11,7 +11,7 @@ impl App {
 
         let snapshot_id = snapshot_id.as_ref();
 
-        let snapshots = self.get_guest_snapshots(&connection, guest_id)?;
+        let snapshots = self.get_guest_snapshots(connection, guest_id)?;
         if !snapshots.contains_key(snapshot_id) {
             std::process::exit(1);
         }
@@ -0,0 +1,195 @@
+use crate::app::path_with_suffix;
+use crate::app::snapshot::Snapshot;
+use crate::app::SshConnection;
+use crate::app::LN_COMMAND;
+use crate::app::MV_COMMAND;
+use crate::app::QEMU_IMG_COMMAND;
+use crate::app::RM_COMMAND;
+use crate::command::Execute;
+use crate::errors::BootedGuestError;
+use crate::errors::LeftoverImageError;
+use crate::errors::UnknownDiskError;
+use crate::errors::VmStateSnapshotError;
+use crate::App;
+use anyhow::Result;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+const COMPACT_SUFFIX: &str = ".compact";
+const ORIGINAL_SUFFIX: &str = ".orig";
+
+impl App {
+    pub fn compact_disk<T>(
+        &self,
+        guest_id: T,
+        disk_id: Option<String>,
+        compress: bool,
+        keep_snapshots: bool,
+    ) -> Result<()>
+    where
+        T: AsRef<str>,
+    {
+        let connection = self.get_host_ssh_connection();
+
+        let guest_id = guest_id.as_ref();
+
+        let disks: Vec<_> = self
+            .get_guest_disks(guest_id)?
+            .iter()
+            .enumerate()
+            .filter(|(_, disk)| match &disk_id {
+                Some(disk_id) => &disk.label == disk_id,
+                None => true,
+            })
+            .collect();
+        if let Some(disk_id) = &disk_id {
+            if disks.is_empty() {
+                anyhow::bail!(UnknownDiskError::new(guest_id, disk_id));
+            }
+        }
+
+        if self.is_booted(connection, guest_id)? {
+            anyhow::bail!(BootedGuestError::new(guest_id));
+        }
+
+        // a leftover from an earlier run would only fail the command after all the
+        // images are already converted
+        for (_, disk) in &disks {
+            let original_path = path_with_suffix(&disk.path, ORIGINAL_SUFFIX);
+            if self.exists(connection, &original_path)? {
+                anyhow::bail!(LeftoverImageError::new(&original_path));
+            }
+        }
+
+        let mut snapshots = Vec::new();
+        if keep_snapshots {
+            for (disk_id, disk) in &disks {
+                let disk_snapshots = self.get_disk_snapshots(connection, guest_id, *disk_id)?;
+                for snapshot in disk_snapshots.into_values() {
+                    // qemu-img has no way to copy the VM state over
+                    if snapshot.vm_state_size > 0 {
+                        anyhow::bail!(VmStateSnapshotError::new(guest_id, &snapshot.id));
+                    }
+                    snapshots.push((&disk.path, snapshot));
+                }
+            }
+            snapshots.sort_by_key(|(_, snapshot)| snapshot.timestamp);
+        }
+        let snapshots = snapshots;
+
+        let paths: Vec<_> = disks.iter().map(|(_, disk)| &disk.path).collect();
+
+        // images left over from an interrupted run are written from scratch, and the ones
+        // of this run are removed again when any of them cannot be written
+        for path in &paths {
+            command_macros::command! {
+                {connection.execute(RM_COMMAND)} -f (path_with_suffix(path, COMPACT_SUFFIX))
+            }
+            .execute()?;
+        }
+        if let Err(error) = self.write_compact_images(connection, &paths, &snapshots, compress) {
+            for path in &paths {
+                let _ = command_macros::command! {
+                    {connection.execute(RM_COMMAND)} -f (path_with_suffix(path, COMPACT_SUFFIX))
+                }
+                .execute();
+            }
+            return Err(error);
+        }
+
+        // the original image stays available under a separate name and the compacted one
+        // replaces it with a single rename, so the disk path always points to a valid image
+        for (_, disk) in &disks {
+            let path = &disk.path;
+            let compact_path = path_with_suffix(path, COMPACT_SUFFIX);
+            let original_path = path_with_suffix(path, ORIGINAL_SUFFIX);
+
+            command_macros::command! {
+                {connection.execute(LN_COMMAND)} (path) (original_path)
+            }
+            .execute()?;
+
+            command_macros::command! {
+                {connection.execute(MV_COMMAND)} (compact_path) (path)
+            }
+            .execute()?;
+        }
+
+        eprint!("Remove original disk images? [y/N] ");
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer).unwrap_or_default();
+
+        if answer.trim().eq_ignore_ascii_case("y") {
+            for (_, disk) in &disks {
+                let original_path = path_with_suffix(&disk.path, ORIGINAL_SUFFIX);
+
+                command_macros::command! {
+                    {connection.execute(RM_COMMAND)} (original_path)
+                }
+                .execute()?;
+            }
+        } else {
+            for (_, disk) in &disks {
+                let original_path = path_with_suffix(&disk.path, ORIGINAL_SUFFIX);
+                println!("{}", original_path.display());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn write_compact_images(
+        &self,
+        connection: &SshConnection,
+        paths: &[&PathBuf],
+        snapshots: &[(&PathBuf, Snapshot)],
+        compress: bool,
+    ) -> Result<()> {
+        // qemu-img convert copies only a single state of the image, so to keep the snapshots
+        // we replay them one by one in chronological order and then write the current state
+        // on top of them; the snapshots of all disks are replayed together, so the ones taken
+        // of the whole guest are recreated close enough to each other to stay common
+        let mut started_paths = HashSet::new();
+        for (path, snapshot) in snapshots {
+            let compact_path = path_with_suffix(path, COMPACT_SUFFIX);
+
+            command_macros::command! {
+                {connection.execute(QEMU_IMG_COMMAND)} convert -q -O qcow2
+                if started_paths.contains(path) {
+                    -n
+                } else {
+                    -olazy_refcounts=on
+                }
+                -l snapshot.name=(snapshot.id) (path) (compact_path)
+            }
+            .execute()?;
+
+            command_macros::command! {
+                {connection.execute(QEMU_IMG_COMMAND)} snapshot -c(snapshot.id) (compact_path)
+            }
+            .execute()?;
+
+            started_paths.insert(*path);
+        }
+
+        for path in paths {
+            let compact_path = path_with_suffix(path, COMPACT_SUFFIX);
+
+            command_macros::command! {
+                {connection.execute(QEMU_IMG_COMMAND)} convert -q -O qcow2
+                if started_paths.contains(*path) {
+                    -n
+                } else {
+                    -olazy_refcounts=on
+                }
+                if compress {
+                    -c
+                }
+                (path) (compact_path)
+            }
+            .execute()?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+use crate::app::SOCAT_COMMAND;
+use crate::app::TAIL_COMMAND;
+use crate::command::Execute;
+use crate::errors::MissingSerialLogError;
+use crate::errors::MissingSerialSocketError;
+use crate::App;
+use anyhow::Result;
+use std::process::Stdio;
+
+impl App {
+    pub fn connect_to_console<T>(&self, guest_id: T, log: bool) -> Result<()>
+    where
+        T: AsRef<str>,
+    {
+        let connection = self.get_host_ssh_connection();
+
+        let guest_id = guest_id.as_ref();
+
+        let guest = self.get_guest(guest_id)?;
+
+        let mut command = if log {
+            let serial_log_path = match &guest.serial_log_path {
+                Some(serial_log_path) => serial_log_path,
+                None => anyhow::bail!(MissingSerialLogError::new(guest_id)),
+            };
+
+            command_macros::command! {
+                {connection.execute_in_terminal(TAIL_COMMAND)} -F (serial_log_path)
+            }
+        } else {
+            let serial_socket_path = match &guest.serial_socket_path {
+                Some(serial_socket_path) => serial_socket_path,
+                None => anyhow::bail!(MissingSerialSocketError::new(guest_id)),
+            };
+
+            // socat exits on Ctrl-], the same escape key telnet uses
+            command_macros::command! {
+                {connection.execute_in_terminal(SOCAT_COMMAND)} STDIO,raw,echo=0,escape=0x1d UNIX-CONNECT:(serial_socket_path)
+            }
+        };
+
+        command
+            .stdin(Stdio::inherit())
+            .stdout(Stdio::inherit())
+            .execute()?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+use crate::command::Execute;
+use crate::errors::InvalidCopyPathsError;
+use crate::App;
+use anyhow::Result;
+use std::process::Stdio;
+
+impl App {
+    pub fn copy_files<T, U>(
+        &self,
+        source: T,
+        target: U,
+        recursive: bool,
+        progress: bool,
+    ) -> Result<()>
+    where
+        T: AsRef<str>,
+        U: AsRef<str>,
+    {
+        let source = source.as_ref();
+        let target = target.as_ref();
+
+        let (connection, source, target) =
+            match (split_guest_path(source), split_guest_path(target)) {
+                (Some((guest_id, path)), None) => {
+                    let connection = self.get_guest_ssh_connection(guest_id)?;
+                    let source = connection.remote_path(path);
+                    (connection, source, target.to_string())
+                }
+                (None, Some((guest_id, path))) => {
+                    let connection = self.get_guest_ssh_connection(guest_id)?;
+                    let target = connection.remote_path(path);
+                    (connection, source.to_string(), target)
+                }
+                _ => anyhow::bail!(InvalidCopyPathsError::new(source, target)),
+            };
+
+        // scp only draws the progress meter on a terminal
+        let mut command = command_macros::command! {
+            {connection.copy()}
+            if recursive {
+                -r
+            }
+            if !progress {
+                -q
+            }
+            (source) (target)
+        };
+        if progress {
+            command.stdout(Stdio::inherit());
+        }
+        command.execute()?;
+
+        Ok(())
+    }
+}
+
+fn split_guest_path(path: &str) -> Option<(&str, &str)> {
+    let (guest_id, path) = path.split_once(':')?;
+
+    // like scp, a colon only separates the guest when no slash comes before
+    // it, so local paths can be written as ./file:name
+    if guest_id.is_empty() || guest_id.contains('/') {
+        return None;
+    }
+
+    Some((guest_id, path))
+}
@@ -0,0 +1,13 @@
+use crate::App;
+use anyhow::Result;
+
+impl App {
+    pub fn create_network<T>(&self, network_id: T) -> Result<()>
+    where
+        T: AsRef<str>,
+    {
+        let connection = self.get_host_ssh_connection();
+
+        self.ensure_network(connection, network_id)
+    }
+}
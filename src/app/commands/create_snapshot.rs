@@ -17,7 +17,7 @@ impl App {
 
         let disks = self.get_guest_disks(guest_id)?;
         for (disk_id, disk) in disks.iter().enumerate() {
-            let snapshots = self.get_disk_snapshots(&connection, guest_id, disk_id)?;
+            let snapshots = self.get_disk_snapshots(connection, guest_id, disk_id)?;
             if snapshots.contains_key(snapshot_id) {
                 anyhow::bail!(DuplicateSnapshotError::new(
                     guest_id,
@@ -27,6 +27,14 @@ impl App {
             }
         }
 
+        let nvram_path = self.get_existing_guest_nvram_path(connection, guest_id)?;
+        if let Some(nvram_path) = &nvram_path {
+            let info = self.get_image_info(connection, nvram_path)?;
+            if info.snapshots.contains_key(snapshot_id) {
+                anyhow::bail!(DuplicateSnapshotError::new(guest_id, "nvram", snapshot_id));
+            }
+        }
+
         for disk in disks {
             command_macros::command! {
                 {connection.execute(QEMU_IMG_COMMAND)} snapshot -c(snapshot_id) (disk.path)
@@ -34,6 +42,13 @@ impl App {
             .execute()?;
         }
 
+        if let Some(nvram_path) = nvram_path {
+            command_macros::command! {
+                {connection.execute(QEMU_IMG_COMMAND)} snapshot -c(snapshot_id) (nvram_path)
+            }
+            .execute()?;
+        }
+
         Ok(())
     }
 }
@@ -0,0 +1,50 @@
+use crate::app::firewall;
+use crate::app::network_mode::NetworkMode;
+use crate::app::IP_COMMAND;
+use crate::command::Execute;
+use crate::errors::NetworkInUseError;
+use crate::App;
+use anyhow::Result;
+
+impl App {
+    pub fn delete_network<T>(&self, network_id: T) -> Result<()>
+    where
+        T: AsRef<str>,
+    {
+        let connection = self.get_host_ssh_connection();
+
+        let network_id = network_id.as_ref();
+
+        let network = self.get_network(network_id)?;
+
+        for (guest_id, guest) in &self.guests {
+            let attached = guest
+                .network_interfaces
+                .iter()
+                .any(|network_interface| network_interface.network_id == network_id);
+            if attached && self.is_booted(connection, guest_id)? {
+                anyhow::bail!(NetworkInUseError::new(network_id, guest_id));
+            }
+        }
+
+        if self.is_dhcp_running(connection, network_id)? {
+            self.stop_dhcp(connection, network_id)?;
+        }
+
+        if network.mode != NetworkMode::Bridged {
+            let ruleset = firewall::delete_table("ip", firewall::network_table(network_id));
+            self.load_firewall_ruleset(connection, ruleset)?;
+        }
+
+        if !self.link_exists(connection, &network.bridge_name)? {
+            return Ok(());
+        }
+
+        command_macros::command! {
+            {connection.execute(IP_COMMAND)} link delete (network.bridge_name) type bridge
+        }
+        .execute()?;
+
+        Ok(())
+    }
+}
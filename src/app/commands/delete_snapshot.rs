@@ -22,6 +22,17 @@ impl App {
             .execute()?;
         }
 
+        // guests switched to uefi later lack the older snapshots in nvram
+        if let Some(nvram_path) = self.get_existing_guest_nvram_path(connection, guest_id)? {
+            let info = self.get_image_info(connection, &nvram_path)?;
+            if info.snapshots.contains_key(snapshot_id) {
+                command_macros::command! {
+                    {connection.execute(QEMU_IMG_COMMAND)} snapshot -d(snapshot_id) (nvram_path)
+                }
+                .execute()?;
+            }
+        }
+
         Ok(())
     }
 }
@@ -0,0 +1,303 @@
+use crate::app::SshConnection;
+use crate::app::SH_COMMAND;
+use crate::app::TIMEOUT_COMMAND;
+use crate::app::TRUE_COMMAND;
+use crate::errors::GuestCommandFailedError;
+use crate::errors::ProcessExecutionError;
+use crate::App;
+use anyhow::Result;
+use base64::Engine;
+use serde::Deserialize;
+use serde::Serialize;
+use std::io::Read;
+use std::process::Child;
+use std::process::ExitStatus;
+use std::process::Stdio;
+use std::time::Duration;
+use std::time::Instant;
+
+// ssh reports its own failures with this code, e.g. when the guest is unreachable
+const SSH_FAILURE_EXIT_CODE: i32 = 255;
+// the same code coreutils timeout uses
+const TIMEOUT_EXIT_CODE: i32 = 124;
+// shells report programs killed by a signal with this offset
+const SIGNAL_EXIT_CODE_OFFSET: i32 = 128;
+
+#[derive(Serialize)]
+struct CommandOutput {
+    guest_id: String,
+    exit_code: i32,
+    stdout: String,
+    stderr: String,
+}
+
+impl App {
+    pub fn execute_in_guests(
+        &self,
+        guest_ids: Vec<String>,
+        args: Vec<String>,
+        timeout: Option<u64>,
+        json: bool,
+    ) -> Result<()> {
+        for guest_id in &guest_ids {
+            self.get_guest(guest_id)?;
+        }
+
+        // guests do not depend on each other, so a slow one does not hold up
+        // the rest
+        let results: Vec<_> = std::thread::scope(|scope| {
+            let handles: Vec<_> = guest_ids
+                .iter()
+                .map(|guest_id| {
+                    let args = &args;
+                    scope.spawn(move || self.execute_guest_command(guest_id, args, timeout))
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect()
+        });
+
+        let mut outputs = Vec::new();
+        let mut failure = None;
+        for result in results {
+            match result {
+                Ok(output) => {
+                    if output.exit_code != 0 && failure.is_none() {
+                        failure = Some(
+                            GuestCommandFailedError::new(&output.guest_id, output.exit_code).into(),
+                        );
+                    }
+                    outputs.push(output);
+                }
+                Err(error) => {
+                    if failure.is_none() {
+                        failure = Some(error);
+                    }
+                }
+            }
+        }
+
+        if json {
+            println!("{}", serde_json::to_string_pretty(&outputs)?);
+        } else if guest_ids.len() == 1 {
+            for output in &outputs {
+                print!("{}", output.stdout);
+                eprint!("{}", output.stderr);
+            }
+        } else {
+            for output in &outputs {
+                for line in output.stdout.lines() {
+                    println!("{}: {line}", output.guest_id);
+                }
+                for line in output.stderr.lines() {
+                    eprintln!("{}: {line}", output.guest_id);
+                }
+            }
+        }
+
+        match failure {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    fn execute_guest_command(
+        &self,
+        guest_id: &str,
+        args: &[String],
+        timeout: Option<u64>,
+    ) -> Result<CommandOutput> {
+        let connection = self.get_guest_ssh_connection(guest_id)?;
+
+        // a guest with broken networking can still be reached through the
+        // agent, as long as it runs, so this is settled before the command
+        // runs, which never happens twice
+        let mut probe = command_macros::command! {
+            {connection.command()} (TRUE_COMMAND)
+        };
+        let probe_output = probe
+            .output()
+            .map_err(|_| ProcessExecutionError::new(&probe))?;
+        if probe_output.status.code() == Some(SSH_FAILURE_EXIT_CODE) {
+            let connection = self.get_host_ssh_connection();
+            if self.is_guest_agent_running(connection, guest_id)? {
+                return self.execute_guest_agent_exec(connection, guest_id, args, timeout);
+            }
+
+            return Ok(CommandOutput {
+                guest_id: guest_id.to_string(),
+                exit_code: SSH_FAILURE_EXIT_CODE,
+                stdout: String::from_utf8_lossy(&probe_output.stdout).to_string(),
+                stderr: String::from_utf8_lossy(&probe_output.stderr).to_string(),
+            });
+        }
+
+        // the remote shell would otherwise only put the first of several
+        // commands under the deadline
+        let mut command = command_macros::command! {
+            {connection.command()}
+            if let Some(timeout) = timeout {
+                (TIMEOUT_COMMAND) ((timeout)) (SH_COMMAND) -c (quote(&args.join(" ")))
+            } else {
+                [args]
+            }
+        };
+        let mut child = command
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|_| ProcessExecutionError::new(&command))?;
+
+        // the deadline is enforced locally as well, since a guest that stops
+        // responding would keep ssh waiting forever
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+        let (status, stdout, stderr) = std::thread::scope(|scope| {
+            let stdout = scope.spawn(move || read_output(stdout));
+            let stderr = scope.spawn(move || read_output(stderr));
+            let status = wait_with_timeout(&mut child, timeout);
+            (status, stdout.join().unwrap(), stderr.join().unwrap())
+        });
+
+        let exit_code = match status.map_err(|_| ProcessExecutionError::new(&command))? {
+            Some(status) => match status.code() {
+                Some(exit_code) => exit_code,
+                None => anyhow::bail!(ProcessExecutionError::new(&command)),
+            },
+            None => TIMEOUT_EXIT_CODE,
+        };
+
+        Ok(CommandOutput {
+            guest_id: guest_id.to_string(),
+            exit_code,
+            stdout,
+            stderr,
+        })
+    }
+
+    fn execute_guest_agent_exec(
+        &self,
+        connection: &SshConnection,
+        guest_id: &str,
+        args: &[String],
+        timeout: Option<u64>,
+    ) -> Result<CommandOutput> {
+        #[derive(Deserialize)]
+        struct GuestExec {
+            pid: i64,
+        }
+
+        #[derive(Deserialize)]
+        struct GuestExecStatus {
+            exited: bool,
+            #[serde(rename = "exitcode")]
+            exit_code: Option<i32>,
+            signal: Option<i32>,
+            #[serde(rename = "out-data")]
+            out_data: Option<String>,
+            #[serde(rename = "err-data")]
+            err_data: Option<String>,
+        }
+
+        // the command is a required argument, so there is at least one
+        // element and we can use unwrap here, unlike ssh the agent runs the
+        // program directly, without a shell
+        let (path, args) = args.split_first().unwrap();
+
+        let exec: GuestExec = self.execute_guest_agent_command(
+            connection,
+            guest_id,
+            "guest-exec",
+            serde_json::json!({ "path": path, "arg": args, "capture-output": true }),
+        )?;
+
+        let delay = Duration::from_millis(1000);
+        let mut elapsed = 0;
+        loop {
+            let status: GuestExecStatus = self.execute_guest_agent_command(
+                connection,
+                guest_id,
+                "guest-exec-status",
+                serde_json::json!({ "pid": exec.pid }),
+            )?;
+            if status.exited {
+                let exit_code = match (status.exit_code, status.signal) {
+                    (Some(exit_code), _) => exit_code,
+                    (None, Some(signal)) => SIGNAL_EXIT_CODE_OFFSET + signal,
+                    (None, None) => 0,
+                };
+                return Ok(CommandOutput {
+                    guest_id: guest_id.to_string(),
+                    exit_code,
+                    stdout: decode_output(status.out_data),
+                    stderr: decode_output(status.err_data),
+                });
+            }
+
+            // the agent has no way to stop the program, it is left running
+            if let Some(timeout) = timeout {
+                if elapsed >= timeout {
+                    return Ok(CommandOutput {
+                        guest_id: guest_id.to_string(),
+                        exit_code: TIMEOUT_EXIT_CODE,
+                        stdout: String::new(),
+                        stderr: String::new(),
+                    });
+                }
+            }
+
+            std::thread::sleep(delay);
+            elapsed += 1;
+        }
+    }
+}
+
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+fn read_output<T>(output: Option<T>) -> String
+where
+    T: Read,
+{
+    let mut data = Vec::new();
+    if let Some(mut output) = output {
+        let _ = output.read_to_end(&mut data);
+    }
+
+    String::from_utf8_lossy(&data).to_string()
+}
+
+// returns no status when the child had to be killed
+fn wait_with_timeout(
+    child: &mut Child,
+    timeout: Option<u64>,
+) -> std::io::Result<Option<ExitStatus>> {
+    let timeout = match timeout {
+        Some(timeout) => Duration::from_secs(timeout),
+        None => return child.wait().map(Some),
+    };
+
+    let start = Instant::now();
+    let delay = Duration::from_millis(100);
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        if start.elapsed() >= timeout {
+            child.kill()?;
+            child.wait()?;
+            return Ok(None);
+        }
+
+        std::thread::sleep(delay);
+    }
+}
+
+fn decode_output(data: Option<String>) -> String {
+    data.and_then(|data| base64::engine::general_purpose::STANDARD.decode(data).ok())
+        .map(|data| String::from_utf8_lossy(&data).to_string())
+        .unwrap_or_default()
+}
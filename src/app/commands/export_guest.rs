@@ -0,0 +1,132 @@
+use crate::app::bundle::disk_member;
+use crate::app::bundle::rename_transform;
+use crate::app::bundle::Manifest;
+use crate::app::bundle::ManifestSnapshot;
+use crate::app::bundle::GUEST_MEMBER;
+use crate::app::bundle::MANIFEST_MEMBER;
+use crate::app::bundle::NVRAM_MEMBER;
+use crate::app::SshConnection;
+use crate::app::MKTEMP_COMMAND;
+use crate::app::RM_COMMAND;
+use crate::app::TAR_COMMAND;
+use crate::command::Execute;
+use crate::errors::BootedGuestError;
+use crate::App;
+use anyhow::Result;
+use std::path::Path;
+use std::path::PathBuf;
+
+impl App {
+    pub fn export_guest<T>(&self, guest_id: T, path: PathBuf) -> Result<()>
+    where
+        T: AsRef<str>,
+    {
+        let connection = self.get_host_ssh_connection();
+
+        let guest_id = guest_id.as_ref();
+
+        self.get_guest(guest_id)?;
+
+        if self.is_booted(connection, guest_id)? {
+            anyhow::bail!(BootedGuestError::new(guest_id));
+        }
+
+        let mut snapshots: Vec<_> = self
+            .get_guest_snapshots(connection, guest_id)?
+            .into_values()
+            .collect();
+        snapshots.sort_by_key(|snapshot| snapshot.timestamp);
+        let nvram_path = self.get_existing_guest_nvram_path(connection, guest_id)?;
+        let manifest = Manifest {
+            guest_id: guest_id.to_string(),
+            snapshots: snapshots
+                .into_iter()
+                .map(|snapshot| ManifestSnapshot {
+                    id: snapshot.id,
+                    timestamp: snapshot.timestamp.as_secs(),
+                })
+                .collect(),
+            nvram: nvram_path.is_some(),
+        };
+
+        let staging_path = command_macros::command! {
+            {connection.execute(MKTEMP_COMMAND)} -d
+        }
+        .execute()?;
+        let staging_path = PathBuf::from(staging_path.trim());
+
+        // the staging directory is removed whether the bundle could be written or not
+        let result = self.write_bundle(
+            connection,
+            guest_id,
+            &manifest,
+            &nvram_path,
+            &path,
+            &staging_path,
+        );
+
+        command_macros::command! {
+            {connection.execute(RM_COMMAND)} -r (staging_path)
+        }
+        .execute()?;
+
+        result
+    }
+
+    fn write_bundle(
+        &self,
+        connection: &SshConnection,
+        guest_id: &str,
+        manifest: &Manifest,
+        nvram_path: &Option<PathBuf>,
+        path: &Path,
+        staging_path: &Path,
+    ) -> Result<()> {
+        let guest = self.get_guest(guest_id)?;
+
+        self.write_file(
+            connection,
+            staging_path.join(GUEST_MEMBER),
+            toml::to_string(guest)?,
+        )?;
+        self.write_file(
+            connection,
+            staging_path.join(MANIFEST_MEMBER),
+            serde_json::to_string_pretty(manifest)?,
+        )?;
+
+        self.create_parent_dir(connection, path)?;
+
+        // disk and nvram images are archived relative to the root and renamed to neutral member
+        // names, so the bundle does not depend on the storage layout of the exporting host
+        let members: Vec<_> = guest
+            .disks
+            .iter()
+            .map(|disk| (&disk.path, disk_member(&disk.label)))
+            .chain(
+                nvram_path
+                    .iter()
+                    .map(|nvram_path| (nvram_path, NVRAM_MEMBER.to_string())),
+            )
+            .collect();
+        let member_paths: Vec<_> = members
+            .iter()
+            .map(|(path, _)| path.strip_prefix("/").unwrap_or(path))
+            .collect();
+        let transforms: Vec<_> = members
+            .iter()
+            .zip(&member_paths)
+            .map(|((_, member), member_path)| {
+                rename_transform(member_path.display().to_string(), member)
+            })
+            .collect();
+        command_macros::command! {
+            {connection.execute(TAR_COMMAND)} --create --sparse --file (path) [transforms]
+            -C (staging_path) (GUEST_MEMBER) (MANIFEST_MEMBER)
+            -C (Path::new("/")) [member_paths]
+        }
+        .execute()?;
+
+        Ok(())
+    }
+}
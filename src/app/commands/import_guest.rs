@@ -0,0 +1,339 @@
+use crate::app::bundle::disk_member;
+use crate::app::bundle::rename_transform;
+use crate::app::bundle::Manifest;
+use crate::app::bundle::GUEST_MEMBER;
+use crate::app::bundle::MANIFEST_MEMBER;
+use crate::app::bundle::NVRAM_MEMBER;
+use crate::app::guest::Guest;
+use crate::app::guest_nvram_path;
+use crate::app::network_interface;
+use crate::app::ssh_connection::SshConnection;
+use crate::app::MKTEMP_COMMAND;
+use crate::app::RM_COMMAND;
+use crate::app::TAR_COMMAND;
+use crate::command::Execute;
+use crate::errors::DuplicateGuestError;
+use crate::errors::ParseBundleError;
+use crate::errors::WriteConfigurationError;
+use crate::App;
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+impl App {
+    pub fn import_guest(&self, path: PathBuf, guest_id: Option<String>) -> Result<()> {
+        let connection = self.get_host_ssh_connection();
+
+        let staging_path = command_macros::command! {
+            {connection.execute(MKTEMP_COMMAND)} -d
+        }
+        .execute()?;
+        let staging_path = PathBuf::from(staging_path.trim());
+
+        // the staging directory is removed whether the bundle could be read or not
+        let members = self.read_bundle_members(connection, &path, &staging_path);
+
+        command_macros::command! {
+            {connection.execute(RM_COMMAND)} -r (staging_path)
+        }
+        .execute()?;
+
+        let (guest, manifest) = members?;
+
+        let mut guest =
+            toml::from_str::<Guest>(&guest).map_err(|_| ParseBundleError::new(&path))?;
+        let manifest = serde_json::from_str::<Manifest>(&manifest)
+            .map_err(|_| ParseBundleError::new(&path))?;
+
+        let original_guest_id = manifest.guest_id.clone();
+        let guest_id = guest_id.unwrap_or_else(|| original_guest_id.clone());
+
+        if self.guests.contains_key(&guest_id) {
+            anyhow::bail!(DuplicateGuestError::new(&guest_id));
+        }
+
+        for network_interface in &guest.network_interfaces {
+            self.get_network(&network_interface.network_id)?;
+        }
+
+        if guest_id != original_guest_id {
+            guest.monitor_socket_path =
+                rename_file(&guest.monitor_socket_path, &original_guest_id, &guest_id);
+            guest.pidfile_path = rename_file(&guest.pidfile_path, &original_guest_id, &guest_id);
+            for disk in &mut guest.disks {
+                disk.path = rename_file(&disk.path, &original_guest_id, &guest_id);
+            }
+            if let Some(nvram_path) = &guest.nvram_path {
+                guest.nvram_path = Some(rename_file(nvram_path, &original_guest_id, &guest_id));
+            }
+            for network_interface in &mut guest.network_interfaces {
+                // names that become too long are generated from the new id instead
+                if let Some(tap_name) = &network_interface.tap_name {
+                    let tap_name = tap_name.replace(&original_guest_id, &guest_id);
+                    network_interface.tap_name = if network_interface::is_valid_tap_name(&tap_name)
+                    {
+                        Some(tap_name)
+                    } else {
+                        None
+                    };
+                }
+            }
+        }
+
+        // everything that has to be unique on the host is remapped when it is already taken
+        let guests = self.guests.values();
+
+        if guests
+            .clone()
+            .any(|other| other.spice_port == guest.spice_port)
+        {
+            let spice_port = guests.clone().map(|other| other.spice_port).max();
+            guest.spice_port = spice_port.unwrap_or(guest.spice_port) + 1;
+        }
+
+        let mut paths: HashSet<_> = guests
+            .clone()
+            .flat_map(|other| {
+                other
+                    .disks
+                    .iter()
+                    .map(|disk| &disk.path)
+                    .chain([&other.monitor_socket_path, &other.pidfile_path])
+                    .chain(&other.nvram_path)
+            })
+            .cloned()
+            .collect();
+        guest.monitor_socket_path =
+            unique_path(&guest.monitor_socket_path, &mut paths, |_| Ok(false))?;
+        guest.pidfile_path = unique_path(&guest.pidfile_path, &mut paths, |_| Ok(false))?;
+        for disk in &mut guest.disks {
+            disk.path = unique_path(&disk.path, &mut paths, |path| self.exists(connection, path))?;
+        }
+        // the default nvram path follows the first disk, so it is only pinned when taken
+        let nvram_path = match guest_nvram_path(&guest_id, &guest)? {
+            Some(nvram_path) if manifest.nvram => {
+                let unique_nvram_path = unique_path(&nvram_path, &mut paths, |path| {
+                    self.exists(connection, path)
+                })?;
+                if unique_nvram_path != nvram_path {
+                    guest.nvram_path = Some(unique_nvram_path.clone());
+                }
+                Some(unique_nvram_path)
+            }
+            _ => None,
+        };
+
+        // generated defaults are only pinned when they collide
+        let network_interfaces =
+            self.guests.iter().flat_map(|(other_guest_id, other)| {
+                other.network_interfaces.iter().enumerate().map(
+                    move |(index, network_interface)| (other_guest_id, index, network_interface),
+                )
+            });
+        let mut mac_addresses: HashSet<_> = network_interfaces
+            .clone()
+            .map(|(other_guest_id, index, network_interface)| {
+                network_interface
+                    .mac_address(other_guest_id, index)
+                    .to_uppercase()
+            })
+            .collect();
+        let mut tap_names: HashSet<_> = network_interfaces
+            .map(|(other_guest_id, index, network_interface)| {
+                network_interface.tap_name(other_guest_id, index)
+            })
+            .collect();
+        for (index, network_interface) in guest.network_interfaces.iter_mut().enumerate() {
+            let mut mac_address = network_interface
+                .mac_address(&guest_id, index)
+                .to_uppercase();
+            if mac_addresses.contains(&mac_address) {
+                while mac_addresses.contains(&mac_address) {
+                    mac_address = next_mac_address(&mac_address);
+                }
+                network_interface.mac_address = Some(mac_address.clone());
+            }
+            mac_addresses.insert(mac_address);
+
+            let tap_name = network_interface.tap_name(&guest_id, index);
+            let mut unique_tap_name = tap_name.clone();
+            let mut suffix = 0;
+            while tap_names.contains(&unique_tap_name) {
+                suffix += 1;
+                let suffix = suffix.to_string();
+                // the limit is in bytes, but names are only cut between characters
+                let mut prefix = String::new();
+                for character in tap_name.chars() {
+                    if prefix.len() + character.len_utf8() + suffix.len()
+                        > network_interface::TAP_NAME_MAX_LENGTH
+                    {
+                        break;
+                    }
+                    prefix.push(character);
+                }
+                unique_tap_name = format!("{prefix}{suffix}");
+            }
+            if unique_tap_name != tap_name {
+                network_interface.tap_name = Some(unique_tap_name.clone());
+            }
+            tap_names.insert(unique_tap_name);
+        }
+
+        // the images are of no use without the configuration, so they are removed again when
+        // anything fails before it is written
+        let result = self
+            .extract_bundle_disks(connection, &path, &guest, &manifest)
+            .and_then(|_| match &nvram_path {
+                Some(nvram_path) => {
+                    self.extract_bundle_member(connection, &path, NVRAM_MEMBER, nvram_path)
+                }
+                None => Ok(()),
+            })
+            .and_then(|_| self.append_guest_configuration(&guest_id, &guest));
+        if let Err(error) = result {
+            for image_path in guest.disks.iter().map(|disk| &disk.path).chain(&nvram_path) {
+                let _ = command_macros::command! {
+                    {connection.execute(RM_COMMAND)} -f (image_path)
+                }
+                .execute();
+            }
+            return Err(error);
+        }
+
+        Ok(())
+    }
+
+    fn read_bundle_members(
+        &self,
+        connection: &SshConnection,
+        path: &Path,
+        staging_path: &Path,
+    ) -> Result<(String, String)> {
+        command_macros::command! {
+            {connection.execute(TAR_COMMAND)} --extract --file (path)
+            -C (staging_path) (GUEST_MEMBER) (MANIFEST_MEMBER)
+        }
+        .execute()?;
+
+        let guest = self.read_file(connection, staging_path.join(GUEST_MEMBER))?;
+        let manifest = self.read_file(connection, staging_path.join(MANIFEST_MEMBER))?;
+
+        Ok((guest, manifest))
+    }
+
+    fn extract_bundle_disks(
+        &self,
+        connection: &SshConnection,
+        path: &Path,
+        guest: &Guest,
+        manifest: &Manifest,
+    ) -> Result<()> {
+        for disk in &guest.disks {
+            self.extract_bundle_member(connection, path, disk_member(&disk.label), &disk.path)?;
+
+            // every disk has to carry the snapshots the manifest promises
+            let snapshots = self.get_image_info(connection, &disk.path)?.snapshots;
+            if manifest
+                .snapshots
+                .iter()
+                .any(|snapshot| !snapshots.contains_key(&snapshot.id))
+            {
+                anyhow::bail!(ParseBundleError::new(path));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn extract_bundle_member<T>(
+        &self,
+        connection: &SshConnection,
+        path: &Path,
+        member: T,
+        target_path: &Path,
+    ) -> Result<()>
+    where
+        T: AsRef<str>,
+    {
+        let member = member.as_ref();
+
+        self.create_parent_dir(connection, target_path)?;
+
+        let relative_path = target_path.strip_prefix("/").unwrap_or(target_path);
+        let transform = rename_transform(member, relative_path.display().to_string());
+        command_macros::command! {
+            {connection.execute(TAR_COMMAND)} --extract --file (path) (transform)
+            -C (Path::new("/")) (member)
+        }
+        .execute()?;
+
+        Ok(())
+    }
+
+    fn append_guest_configuration(&self, guest_id: &str, guest: &Guest) -> Result<()> {
+        #[derive(Serialize)]
+        struct Configuration<'a> {
+            guests: BTreeMap<&'a str, &'a Guest>,
+        }
+
+        let configuration = toml::to_string(&Configuration {
+            guests: BTreeMap::from([(guest_id, guest)]),
+        })?;
+
+        let config_path = &self.config_path;
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(config_path)
+            .and_then(|mut file| write!(file, "\n{configuration}"))
+            .map_err(|_| WriteConfigurationError::new(config_path))?;
+
+        Ok(())
+    }
+}
+
+fn rename_file(path: &Path, from: &str, to: &str) -> PathBuf {
+    match path.file_name() {
+        Some(file_name) => path.with_file_name(file_name.to_string_lossy().replace(from, to)),
+        None => path.to_owned(),
+    }
+}
+
+fn unique_path<F>(path: &Path, paths: &mut HashSet<PathBuf>, exists: F) -> Result<PathBuf>
+where
+    F: Fn(&Path) -> Result<bool>,
+{
+    let mut unique_path = path.to_owned();
+    let mut index = 0;
+    while paths.contains(&unique_path) || exists(&unique_path)? {
+        index += 1;
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let file_name = match path.extension() {
+            Some(extension) => format!("{stem}-{index}.{}", extension.to_string_lossy()),
+            None => format!("{stem}-{index}"),
+        };
+        unique_path = path.with_file_name(file_name);
+    }
+    paths.insert(unique_path.clone());
+
+    Ok(unique_path)
+}
+
+fn next_mac_address(mac_address: &str) -> String {
+    let octets: Vec<_> = mac_address.split(':').collect();
+    let (prefix, suffix) = octets.split_at(octets.len().saturating_sub(3));
+
+    let suffix = u32::from_str_radix(&suffix.concat(), 16).unwrap_or_default();
+    let suffix = (suffix + 1) & 0xFF_FF_FF;
+
+    format!(
+        "{prefix}:{:02X}:{:02X}:{:02X}",
+        suffix >> 16,
+        (suffix >> 8) & 0xFF,
+        suffix & 0xFF,
+        prefix = prefix.join(":"),
+    )
+}
@@ -1,10 +1,16 @@
+use crate::app::backup;
+use crate::app::OVMF_SECURE_BOOT_VARS_PATH;
+use crate::app::OVMF_VARS_PATH;
 use crate::app::QEMU_IMG_COMMAND;
 use crate::command::Execute;
+use crate::errors::ImageTooLargeError;
+use crate::errors::UnusedImageError;
 use crate::App;
 use anyhow::Result;
+use std::path::PathBuf;
 
 impl App {
-    pub fn initialize_guest<T>(&self, guest_id: T) -> Result<()>
+    pub fn initialize_guest<T>(&self, guest_id: T, image_path: Option<PathBuf>) -> Result<()>
     where
         T: AsRef<str>,
     {
@@ -14,25 +20,82 @@ impl App {
 
         let disks = self.get_guest_disks(guest_id)?;
         let mut missing_disks = Vec::new();
-        for disk in disks {
-            if !self.exists(&connection, &disk.path)? {
-                missing_disks.push(disk);
+        for (disk_id, disk) in disks.iter().enumerate() {
+            if !self.exists(connection, &disk.path)? {
+                missing_disks.push((disk_id, disk));
             }
         }
         let missing_disks = missing_disks;
 
-        for disk in &missing_disks {
+        // the image only ever becomes the first disk, which is the one the
+        // guest boots from, and an existing disk is never overwritten
+        let image_path = match (image_path, missing_disks.first()) {
+            (Some(image_path), Some((0, disk))) => {
+                let info = self.get_image_info(connection, &image_path)?;
+                if info.virtual_size > (disk.size as u64) << 30 {
+                    anyhow::bail!(ImageTooLargeError::new(guest_id, &disk.label, &image_path));
+                }
+                Some(image_path)
+            }
+            (Some(image_path), _) => anyhow::bail!(UnusedImageError::new(guest_id, &image_path)),
+            (None, _) => None,
+        };
+
+        let guest = self.get_guest(guest_id)?;
+        let missing_nvram_path = match self.get_guest_nvram_path(guest_id)? {
+            Some(nvram_path) if !self.exists(connection, &nvram_path)? => Some(nvram_path),
+            _ => None,
+        };
+
+        for (disk_id, disk) in &missing_disks {
             let path = &disk.path;
 
-            self.create_parent_dir(&connection, path)?;
+            self.create_parent_dir(connection, path)?;
+
+            match &image_path {
+                // qemu-img detects the format of the image on its own
+                Some(image_path) if *disk_id == 0 => {
+                    command_macros::command! {
+                        {connection.execute(QEMU_IMG_COMMAND)} convert -q -Oqcow2 -olazy_refcounts=on (image_path) (path)
+                    }
+                    .execute()?;
+
+                    command_macros::command! {
+                        {connection.execute(QEMU_IMG_COMMAND)} resize -q (path) ((disk.size))G
+                    }
+                    .execute()?;
+                }
+                _ => {
+                    command_macros::command! {
+                        {connection.execute(QEMU_IMG_COMMAND)} create -q -fqcow2 -olazy_refcounts=on -opreallocation=metadata (path) ((disk.size))G
+                    }
+                    .execute()?;
+                }
+            }
+
+            command_macros::command! {
+                {connection.execute(QEMU_IMG_COMMAND)} bitmap --add (path) (backup::BITMAP_NAME)
+            }
+            .execute()?;
+        }
+
+        // every guest gets its own copy of the variables template
+        if let Some(nvram_path) = &missing_nvram_path {
+            let template_path = if guest.secure_boot {
+                OVMF_SECURE_BOOT_VARS_PATH
+            } else {
+                OVMF_VARS_PATH
+            };
+
+            self.create_parent_dir(connection, nvram_path)?;
 
             command_macros::command! {
-                {connection.execute(QEMU_IMG_COMMAND)} create -q -fqcow2 -olazy_refcounts=on -opreallocation=metadata (path) ((disk.size))G
+                {connection.execute(QEMU_IMG_COMMAND)} convert -q -f raw -O qcow2 (template_path) (nvram_path)
             }
             .execute()?;
         }
 
-        for disk in &missing_disks {
+        for (_, disk) in &missing_disks {
             let path = &disk.path;
 
             command_macros::command! {
@@ -41,6 +104,13 @@ impl App {
             .execute()?;
         }
 
+        if let Some(nvram_path) = &missing_nvram_path {
+            command_macros::command! {
+                {connection.execute(QEMU_IMG_COMMAND)} snapshot -croot (nvram_path)
+            }
+            .execute()?;
+        }
+
         Ok(())
     }
 }
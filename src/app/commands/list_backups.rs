@@ -0,0 +1,71 @@
+use crate::app::backup;
+use crate::app::format_size;
+use crate::app::DU_COMMAND;
+use crate::command::Execute;
+use crate::App;
+use anyhow::Result;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use tabwriter::TabWriter;
+
+impl App {
+    pub fn list_backups<T, U>(&self, guest_id: T, path: U) -> Result<()>
+    where
+        T: AsRef<str>,
+        U: AsRef<Path>,
+    {
+        let connection = self.get_host_ssh_connection();
+
+        let guest_id = guest_id.as_ref();
+        let path = path.as_ref();
+
+        self.get_guest(guest_id)?;
+
+        let backups = self.get_guest_backups(connection, guest_id, path)?;
+        let backup_paths: Vec<_> = backups
+            .iter()
+            .map(|backup_id| backup::backup_path(path, guest_id, backup_id))
+            .collect();
+
+        let mut sizes = HashMap::new();
+        if !backup_paths.is_empty() {
+            let output = command_macros::command! {
+                {connection.execute(DU_COMMAND)} --block-size=1 --summarize [&backup_paths]
+            }
+            .execute()?;
+            for line in output.lines() {
+                if let Some((size, path)) = line.split_once('\t') {
+                    if let Ok(size) = size.trim().parse::<u64>() {
+                        sizes.insert(PathBuf::from(path.trim()), size);
+                    }
+                }
+            }
+        }
+
+        let mut tw = TabWriter::new(std::io::stdout());
+        writeln!(tw, "ID\tTYPE\tSIZE\tPATH").unwrap();
+        for (backup_id, backup_path) in backups.iter().zip(&backup_paths) {
+            let backup_type =
+                if self.is_incremental_backup(connection, guest_id, path, backup_id)? {
+                    "incremental"
+                } else {
+                    "full"
+                };
+            let size = match sizes.get(backup_path) {
+                Some(size) => format_size(*size),
+                None => "-".to_string(),
+            };
+            writeln!(
+                tw,
+                "{backup_id}\t{backup_type}\t{size}\t{backup_path}",
+                backup_path = backup_path.display(),
+            )
+            .unwrap();
+        }
+        tw.flush().unwrap();
+
+        Ok(())
+    }
+}
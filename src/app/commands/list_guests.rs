@@ -13,7 +13,7 @@ impl App {
             writeln!(
                 tw,
                 "{id}\t{booted}\t{spice_port}\t{description}",
-                booted = self.is_booted(&connection, id)?,
+                booted = self.is_booted(connection, id)?,
                 description = guest.description,
                 spice_port = guest.spice_port,
             )
@@ -0,0 +1,64 @@
+use crate::App;
+use anyhow::Result;
+use std::io::Write;
+use tabwriter::TabWriter;
+
+impl App {
+    pub fn list_networks(&self) -> Result<()> {
+        let connection = self.get_host_ssh_connection();
+
+        let mut tw = TabWriter::new(std::io::stdout());
+        writeln!(tw, "ID\tBRIDGE\tSTATE\tADDRESSES\tTAPS\tGUESTS").unwrap();
+        for (id, network) in &self.networks {
+            let (state, addresses, taps) =
+                match self.get_link_info(connection, &network.bridge_name)? {
+                    Some(info) => (
+                        info.state,
+                        info.addresses,
+                        self.get_bridge_ports(connection, &network.bridge_name)?,
+                    ),
+                    None => ("MISSING".to_string(), Vec::new(), Vec::new()),
+                };
+
+            let guest_ids: Vec<_> = self
+                .guests
+                .iter()
+                .filter(|(_, guest)| {
+                    guest
+                        .network_interfaces
+                        .iter()
+                        .any(|network_interface| &network_interface.network_id == id)
+                })
+                .map(|(guest_id, _)| guest_id.as_str())
+                .collect();
+
+            writeln!(
+                tw,
+                "{id}\t{bridge_name}\t{state}\t{addresses}\t{taps}\t{guest_ids}",
+                addresses = join_or_dash(&addresses),
+                bridge_name = network.bridge_name,
+                guest_ids = join_or_dash(&guest_ids),
+                taps = join_or_dash(&taps),
+            )
+            .unwrap();
+        }
+        tw.flush().unwrap();
+
+        Ok(())
+    }
+}
+
+fn join_or_dash<T>(values: &[T]) -> String
+where
+    T: AsRef<str>,
+{
+    if values.is_empty() {
+        return "-".to_string();
+    }
+
+    values
+        .iter()
+        .map(|value| value.as_ref())
+        .collect::<Vec<_>>()
+        .join(",")
+}
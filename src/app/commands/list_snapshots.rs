@@ -1,3 +1,4 @@
+use crate::app::format_size;
 use crate::App;
 use anyhow::Result;
 use std::io::Write;
@@ -12,7 +13,7 @@ impl App {
         let connection = self.get_host_ssh_connection();
 
         let mut snapshots: Vec<_> = self
-            .get_guest_snapshots(&connection, guest_id)?
+            .get_guest_snapshots(connection, guest_id)?
             .into_values()
             .collect();
         snapshots.sort_by_key(|snapshot| snapshot.timestamp);
@@ -21,14 +22,15 @@ impl App {
         let format =
             time::format_description::parse("[year]-[month]-[day] [hour]:[minute]:[second]")?;
         let mut tw = TabWriter::new(std::io::stdout());
-        writeln!(tw, "ID\tTIMESTAMP").unwrap();
+        writeln!(tw, "ID\tTIMESTAMP\tVM STATE").unwrap();
         for snapshot in snapshots {
             let timestamp = OffsetDateTime::UNIX_EPOCH + snapshot.timestamp;
             writeln!(
                 tw,
-                "{id}\t{timestamp}",
+                "{id}\t{timestamp}\t{vm_state_size}",
                 id = snapshot.id,
                 timestamp = timestamp.format(&format)?,
+                vm_state_size = format_size(snapshot.vm_state_size),
             )
             .unwrap();
         }
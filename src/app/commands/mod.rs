@@ -1,12 +1,29 @@
 pub mod apply_snapshot;
+pub mod backup_guest;
+pub mod check_disks;
 pub mod check_snapshot;
+pub mod compact_disk;
+pub mod connect_to_console;
 pub mod connect_to_guest;
+pub mod copy_files;
+pub mod create_network;
 pub mod create_snapshot;
+pub mod delete_network;
 pub mod delete_snapshot;
+pub mod execute_in_guests;
+pub mod export_guest;
+pub mod import_guest;
 pub mod initialize_guest;
+pub mod list_backups;
 pub mod list_guests;
+pub mod list_networks;
 pub mod list_snapshots;
+pub mod repair_guest;
+pub mod restore_guest;
+pub mod show_disk_usage;
 pub mod show_guest_details;
+pub mod show_guest_info;
+pub mod show_network;
 pub mod start_guest;
 pub mod stop_guest;
 pub mod wait_for_guest_to_shutdown;
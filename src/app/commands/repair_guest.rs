@@ -0,0 +1,40 @@
+use crate::errors::MissingTapError;
+use crate::App;
+use anyhow::Result;
+
+impl App {
+    pub fn repair_guest<T>(&self, guest_id: T) -> Result<()>
+    where
+        T: AsRef<str>,
+    {
+        let connection = self.get_host_ssh_connection();
+
+        let guest_id = guest_id.as_ref();
+
+        let guest = self.get_guest(guest_id)?;
+
+        if !self.is_booted(connection, guest_id)? {
+            self.remove_stale_taps(connection, guest_id)?;
+            self.stop_tpm(connection, guest_id)?;
+            self.remove_guest_firewall(connection, guest_id)?;
+            return Ok(());
+        }
+
+        for (index, network_interface) in guest.network_interfaces.iter().enumerate() {
+            let network = self.get_network(&network_interface.network_id)?;
+
+            let tap_name = network_interface.tap_name(guest_id, index);
+            let info = match self.get_link_info(connection, &tap_name)? {
+                Some(info) => info,
+                None => anyhow::bail!(MissingTapError::new(guest_id, &tap_name)),
+            };
+            if info.master.as_ref() != Some(&network.bridge_name) {
+                self.attach_tap(connection, network_interface, &tap_name)?;
+            }
+        }
+
+        self.ensure_guest_firewall(connection, guest_id)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,118 @@
+use crate::app::backup;
+use crate::app::path_with_suffix;
+use crate::app::SshConnection;
+use crate::app::MV_COMMAND;
+use crate::app::QEMU_IMG_COMMAND;
+use crate::app::RM_COMMAND;
+use crate::command::Execute;
+use crate::errors::BootedGuestError;
+use crate::errors::SnapshotLossError;
+use crate::errors::UnknownBackupError;
+use crate::App;
+use anyhow::Result;
+use std::path::Path;
+use std::path::PathBuf;
+
+const RESTORE_SUFFIX: &str = ".restore";
+
+impl App {
+    pub fn restore_guest<T, U, V>(
+        &self,
+        guest_id: T,
+        backup_id: U,
+        path: V,
+        force: bool,
+    ) -> Result<()>
+    where
+        T: AsRef<str>,
+        U: AsRef<str>,
+        V: AsRef<Path>,
+    {
+        let connection = self.get_host_ssh_connection();
+
+        let guest_id = guest_id.as_ref();
+        let backup_id = backup_id.as_ref();
+        let path = path.as_ref();
+
+        let disks = self.get_guest_disks(guest_id)?;
+
+        if self.is_booted(connection, guest_id)? {
+            anyhow::bail!(BootedGuestError::new(guest_id));
+        }
+
+        let backups = self.get_guest_backups(connection, guest_id, path)?;
+        if !backups.iter().any(|id| id == backup_id) {
+            anyhow::bail!(UnknownBackupError::new(guest_id, backup_id));
+        }
+
+        // qemu-img convert writes only the state of the backup, so the internal snapshots
+        // of the current disks are gone once they are replaced
+        if !force {
+            for (disk_id, disk) in disks.iter().enumerate() {
+                if !self.exists(connection, &disk.path)? {
+                    continue;
+                }
+
+                let snapshots = self.get_disk_snapshots(connection, guest_id, disk_id)?;
+                if let Some(snapshot_id) = snapshots.keys().min() {
+                    anyhow::bail!(SnapshotLossError::new(guest_id, snapshot_id));
+                }
+            }
+        }
+
+        let backup_path = backup::backup_path(path, guest_id, backup_id);
+        let mut images: Vec<_> = disks
+            .iter()
+            .map(|disk| (backup::disk_path(&backup_path, &disk.label), &disk.path))
+            .collect();
+
+        // backups taken before the guest switched to uefi carry no nvram
+        let nvram_path = self.get_guest_nvram_path(guest_id)?;
+        let nvram_source_path = backup::nvram_path(&backup_path);
+        if let Some(nvram_path) = &nvram_path {
+            if self.exists(connection, &nvram_source_path)? {
+                images.push((nvram_source_path, nvram_path));
+            }
+        }
+
+        if let Err(error) = self.write_restore_images(connection, &images) {
+            for (_, image_path) in &images {
+                let _ = command_macros::command! {
+                    {connection.execute(RM_COMMAND)} -f (path_with_suffix(image_path, RESTORE_SUFFIX))
+                }
+                .execute();
+            }
+            return Err(error);
+        }
+
+        // replace images only when every image has been restored successfully
+        for (_, image_path) in &images {
+            let restore_path = path_with_suffix(image_path, RESTORE_SUFFIX);
+
+            command_macros::command! {
+                {connection.execute(MV_COMMAND)} (restore_path) (image_path)
+            }
+            .execute()?;
+        }
+
+        Ok(())
+    }
+
+    fn write_restore_images(
+        &self,
+        connection: &SshConnection,
+        images: &[(PathBuf, &PathBuf)],
+    ) -> Result<()> {
+        for (source_path, image_path) in images {
+            let restore_path = path_with_suffix(image_path, RESTORE_SUFFIX);
+
+            self.create_parent_dir(connection, image_path)?;
+            command_macros::command! {
+                {connection.execute(QEMU_IMG_COMMAND)} convert -q -O qcow2 -olazy_refcounts=on (source_path) (restore_path)
+            }
+            .execute()?;
+        }
+
+        Ok(())
+    }
+}
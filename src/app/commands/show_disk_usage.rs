@@ -0,0 +1,100 @@
+use crate::app::format_size;
+use crate::app::DF_COMMAND;
+use crate::command::Execute;
+use crate::App;
+use anyhow::Result;
+use std::collections::BTreeSet;
+use std::io::Write;
+use tabwriter::TabWriter;
+
+impl App {
+    pub fn show_disk_usage(&self) -> Result<()> {
+        let connection = self.get_host_ssh_connection();
+
+        let mut tw = TabWriter::new(std::io::stdout());
+
+        let mut storage_paths = BTreeSet::new();
+
+        writeln!(
+            tw,
+            "DISKS\tGUEST\tLABEL\tVIRTUAL\tACTUAL\tSNAPSHOTS\tVM STATE\tPATH"
+        )
+        .unwrap();
+        for (guest_id, guest) in &self.guests {
+            for (disk_id, disk) in guest.disks.iter().enumerate() {
+                if let Some(parent_path) = disk.path.parent() {
+                    storage_paths.insert(parent_path);
+                }
+
+                if !self.exists(connection, &disk.path)? {
+                    continue;
+                }
+
+                let info = self.get_disk_info(connection, guest_id, disk_id)?;
+                let vm_state_size = info
+                    .snapshots
+                    .values()
+                    .map(|snapshot| snapshot.vm_state_size)
+                    .sum();
+
+                writeln!(
+                    tw,
+                    "\t{guest_id}\t{label}\t{virtual_size}\t{actual_size}\t{snapshots}\t{vm_state_size}\t{path}",
+                    actual_size = format_size(info.actual_size),
+                    label = disk.label,
+                    path = disk.path.display(),
+                    snapshots = info.snapshots.len(),
+                    virtual_size = format_size(info.virtual_size),
+                    vm_state_size = format_size(vm_state_size),
+                )
+                .unwrap();
+            }
+        }
+        writeln!(tw).unwrap();
+        tw.flush().unwrap();
+
+        // storage paths may not exist yet, in that case df would fail, so we skip them
+        let mut existing_storage_paths = Vec::new();
+        for path in storage_paths {
+            if self.exists(connection, path)? {
+                existing_storage_paths.push(path);
+            }
+        }
+        let existing_storage_paths = existing_storage_paths;
+
+        writeln!(tw, "STORAGE\tMOUNTPOINT\tSIZE\tUSED\tAVAILABLE\tUSE%").unwrap();
+        if !existing_storage_paths.is_empty() {
+            let output = command_macros::command! {
+                {connection.execute(DF_COMMAND)} --block-size=1 --output=target,size,used,avail,pcent [existing_storage_paths]
+            }
+            .execute()?;
+
+            // skip the header and report every filesystem only once
+            let mut mountpoints = BTreeSet::new();
+            for line in output.lines().skip(1) {
+                let fields: Vec<_> = line.split_whitespace().collect();
+                if let [mountpoint, size, used, available, usage] = fields[..] {
+                    if !mountpoints.insert(mountpoint) {
+                        continue;
+                    }
+
+                    let size = size.parse().unwrap_or_default();
+                    let used = used.parse().unwrap_or_default();
+                    let available = available.parse().unwrap_or_default();
+
+                    writeln!(
+                        tw,
+                        "\t{mountpoint}\t{size}\t{used}\t{available}\t{usage}",
+                        available = format_size(available),
+                        size = format_size(size),
+                        used = format_size(used),
+                    )
+                    .unwrap();
+                }
+            }
+        }
+        tw.flush().unwrap();
+
+        Ok(())
+    }
+}
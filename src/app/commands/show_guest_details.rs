@@ -1,3 +1,4 @@
+use crate::app::format_size;
 use crate::App;
 use anyhow::Result;
 use std::io::Write;
@@ -20,7 +21,7 @@ impl App {
         writeln!(
             tw,
             "\t{guest_id}\t{booted}\t{spice_port}\t{memory}\t{cores}\t{description}",
-            booted = self.is_booted(&connection, guest_id)?,
+            booted = self.is_booted(connection, guest_id)?,
             cores = guest.cores,
             description = guest.description,
             memory = guest.memory,
@@ -30,11 +31,21 @@ impl App {
         writeln!(tw).unwrap();
         tw.flush().unwrap();
 
-        writeln!(tw, "DISKS\tLABEL\tSIZE\tPATH").unwrap();
-        for disk in &guest.disks {
+        writeln!(tw, "DISKS\tLABEL\tSIZE\tVIRTUAL\tACTUAL\tPATH").unwrap();
+        for (disk_id, disk) in guest.disks.iter().enumerate() {
+            let (virtual_size, actual_size) = if self.exists(connection, &disk.path)? {
+                let info = self.get_disk_info(connection, guest_id, disk_id)?;
+                (
+                    format_size(info.virtual_size),
+                    format_size(info.actual_size),
+                )
+            } else {
+                ("-".to_string(), "-".to_string())
+            };
+
             writeln!(
                 tw,
-                "\t{label}\t{size}\t{path}",
+                "\t{label}\t{size}\t{virtual_size}\t{actual_size}\t{path}",
                 label = disk.label,
                 path = disk.path.display(),
                 size = disk.size,
@@ -44,15 +55,20 @@ impl App {
         writeln!(tw).unwrap();
         tw.flush().unwrap();
 
-        writeln!(tw, "NETWORK INTERFACES\tNETWORK\tMODEL\tMAC\tTAP").unwrap();
-        for network_interface in &guest.network_interfaces {
+        writeln!(tw, "NETWORK INTERFACES\tNETWORK\tMODEL\tMAC\tTAP\tVLAN").unwrap();
+        for (index, network_interface) in guest.network_interfaces.iter().enumerate() {
+            let vlan = match network_interface.vlan {
+                Some(vlan) => vlan.to_string(),
+                None => "-".to_string(),
+            };
+
             writeln!(
                 tw,
-                "\t{id}\t{model}\t{mac_address}\t{tap_name}",
+                "\t{id}\t{model}\t{mac_address}\t{tap_name}\t{vlan}",
                 id = network_interface.network_id,
-                mac_address = network_interface.mac_address,
+                mac_address = network_interface.mac_address(guest_id, index),
                 model = network_interface.model,
-                tap_name = network_interface.tap_name,
+                tap_name = network_interface.tap_name(guest_id, index),
             )
             .unwrap();
         }
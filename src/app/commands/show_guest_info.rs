@@ -0,0 +1,104 @@
+use crate::App;
+use anyhow::Result;
+use serde::Deserialize;
+use std::io::Write;
+use tabwriter::TabWriter;
+
+impl App {
+    pub fn show_guest_info<T>(&self, guest_id: T) -> Result<()>
+    where
+        T: AsRef<str>,
+    {
+        let connection = self.get_host_ssh_connection();
+
+        let guest_id = guest_id.as_ref();
+
+        #[derive(Deserialize)]
+        struct HostName {
+            #[serde(rename = "host-name")]
+            host_name: String,
+        }
+
+        #[derive(Deserialize)]
+        struct OsInfo {
+            #[serde(rename = "pretty-name")]
+            pretty_name: Option<String>,
+            #[serde(rename = "kernel-release")]
+            kernel_release: Option<String>,
+        }
+
+        #[derive(Deserialize)]
+        struct NetworkInterface {
+            name: String,
+            #[serde(rename = "hardware-address")]
+            hardware_address: Option<String>,
+            #[serde(default, rename = "ip-addresses")]
+            ip_addresses: Vec<IpAddress>,
+        }
+
+        #[derive(Deserialize)]
+        struct IpAddress {
+            #[serde(rename = "ip-address")]
+            ip_address: String,
+            prefix: u32,
+        }
+
+        let host_name: HostName = self.execute_guest_agent_command(
+            connection,
+            guest_id,
+            "guest-get-host-name",
+            serde_json::json!({}),
+        )?;
+        let os_info: OsInfo = self.execute_guest_agent_command(
+            connection,
+            guest_id,
+            "guest-get-osinfo",
+            serde_json::json!({}),
+        )?;
+        let network_interfaces: Vec<NetworkInterface> = self.execute_guest_agent_command(
+            connection,
+            guest_id,
+            "guest-network-get-interfaces",
+            serde_json::json!({}),
+        )?;
+
+        let mut tw = TabWriter::new(std::io::stdout());
+
+        writeln!(tw, "GUEST\tID\tHOSTNAME\tOS\tKERNEL").unwrap();
+        writeln!(
+            tw,
+            "\t{guest_id}\t{host_name}\t{os}\t{kernel}",
+            host_name = host_name.host_name,
+            kernel = os_info.kernel_release.as_deref().unwrap_or("-"),
+            os = os_info.pretty_name.as_deref().unwrap_or("-"),
+        )
+        .unwrap();
+        writeln!(tw).unwrap();
+        tw.flush().unwrap();
+
+        writeln!(tw, "NETWORK INTERFACES\tNAME\tMAC\tADDRESSES").unwrap();
+        for network_interface in &network_interfaces {
+            let addresses: Vec<_> = network_interface
+                .ip_addresses
+                .iter()
+                .map(|address| format!("{}/{}", address.ip_address, address.prefix))
+                .collect();
+            let addresses = if addresses.is_empty() {
+                "-".to_string()
+            } else {
+                addresses.join(" ")
+            };
+
+            writeln!(
+                tw,
+                "\t{name}\t{mac_address}\t{addresses}",
+                mac_address = network_interface.hardware_address.as_deref().unwrap_or("-"),
+                name = network_interface.name,
+            )
+            .unwrap();
+        }
+        tw.flush().unwrap();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,99 @@
+use crate::App;
+use anyhow::Result;
+use std::io::Write;
+use tabwriter::TabWriter;
+
+impl App {
+    pub fn show_network<T>(&self, network_id: T) -> Result<()>
+    where
+        T: AsRef<str>,
+    {
+        let connection = self.get_host_ssh_connection();
+
+        let network_id = network_id.as_ref();
+
+        let network = self.get_network(network_id)?;
+
+        let (state, addresses, taps) = match self.get_link_info(connection, &network.bridge_name)? {
+            Some(info) => (
+                info.state,
+                info.addresses,
+                self.get_bridge_ports(connection, &network.bridge_name)?,
+            ),
+            None => ("MISSING".to_string(), Vec::new(), Vec::new()),
+        };
+
+        let mut tw = TabWriter::new(std::io::stdout());
+
+        let dhcp = if network.dhcp.is_none() {
+            "-"
+        } else if self.is_dhcp_running(connection, network_id)? {
+            "running"
+        } else {
+            "stopped"
+        };
+
+        writeln!(tw, "NETWORK\tID\tBRIDGE\tMODE\tSTATE\tDHCP").unwrap();
+        writeln!(
+            tw,
+            "\t{network_id}\t{bridge_name}\t{mode}\t{state}\t{dhcp}",
+            bridge_name = network.bridge_name,
+            mode = network.mode.as_str(),
+        )
+        .unwrap();
+        writeln!(tw).unwrap();
+        tw.flush().unwrap();
+
+        writeln!(tw, "ADDRESSES\tADDRESS").unwrap();
+        for address in &addresses {
+            writeln!(tw, "\t{address}").unwrap();
+        }
+        writeln!(tw).unwrap();
+        tw.flush().unwrap();
+
+        writeln!(tw, "TAPS\tNAME\tGUEST").unwrap();
+        for tap_name in &taps {
+            let guest_id =
+                self.guests
+                    .iter()
+                    .find(|(guest_id, guest)| {
+                        guest.network_interfaces.iter().enumerate().any(
+                            |(index, network_interface)| {
+                                &network_interface.tap_name(guest_id, index) == tap_name
+                            },
+                        )
+                    })
+                    .map_or("-", |(guest_id, _)| guest_id.as_str());
+            writeln!(tw, "\t{tap_name}\t{guest_id}").unwrap();
+        }
+        writeln!(tw).unwrap();
+        tw.flush().unwrap();
+
+        writeln!(tw, "GUESTS\tID\tBOOTED\tMAC\tTAP").unwrap();
+        for (guest_id, guest) in &self.guests {
+            let network_interfaces: Vec<_> = guest
+                .network_interfaces
+                .iter()
+                .enumerate()
+                .filter(|(_, network_interface)| network_interface.network_id == network_id)
+                .collect();
+            if network_interfaces.is_empty() {
+                continue;
+            }
+
+            let booted = self.is_booted(connection, guest_id)?;
+            for (index, network_interface) in network_interfaces {
+                writeln!(
+                    tw,
+                    "\t{guest_id}\t{booted}\t{mac_address}\t{tap_name}",
+                    mac_address = network_interface.mac_address(guest_id, index),
+                    tap_name = network_interface.tap_name(guest_id, index),
+                )
+                .unwrap();
+            }
+        }
+        tw.flush().unwrap();
+
+        Ok(())
+    }
+}
@@ -1,9 +1,27 @@
+use crate::app::guest::Guest;
+use crate::app::network_interface;
+use crate::app::network_interface::NetworkInterface;
+use crate::app::network_mode::NetworkMode;
+use crate::app::parse_ipv4_network;
+use crate::app::tpm;
+use crate::app::SshConnection;
 use crate::app::CHMOD_COMMAND;
-use crate::app::IP_COMMAND;
+use crate::app::GENISOIMAGE_COMMAND;
+use crate::app::MKTEMP_COMMAND;
+use crate::app::OVMF_CODE_PATH;
+use crate::app::OVMF_SECURE_BOOT_CODE_PATH;
 use crate::app::QEMU_COMMAND;
+use crate::app::QEMU_USER;
+use crate::app::RM_COMMAND;
 use crate::command::Execute;
+use crate::errors::InvalidTapNameError;
+use crate::errors::NetworkInterfaceConflictError;
+use crate::errors::ReadUserDataError;
+use crate::errors::UnsupportedMultiqueueError;
 use crate::App;
 use anyhow::Result;
+use std::net::Ipv4Addr;
+use std::path::Path;
 use std::path::PathBuf;
 
 impl App {
@@ -24,33 +42,97 @@ impl App {
 
         let guest = self.get_guest(guest_id)?;
 
-        if self.is_booted(&connection, guest_id)? {
+        if self.is_booted(connection, guest_id)? {
             return Ok(());
         }
 
-        self.create_parent_dir(&connection, &guest.monitor_socket_path)?;
-        self.create_parent_dir(&connection, &guest.pidfile_path)?;
+        self.check_network_interfaces(guest_id)?;
 
-        command_macros::command! {
+        let nvram_path = self.get_guest_nvram_path(guest_id)?;
+        let code_path = if guest.secure_boot {
+            OVMF_SECURE_BOOT_CODE_PATH
+        } else {
+            OVMF_CODE_PATH
+        };
+
+        if self.ensure_networks {
+            let mut network_ids: Vec<_> = guest
+                .network_interfaces
+                .iter()
+                .map(|network_interface| &network_interface.network_id)
+                .collect();
+            network_ids.sort();
+            network_ids.dedup();
+            for network_id in network_ids {
+                self.ensure_network(connection, network_id)?;
+            }
+        }
+
+        self.create_parent_dir(connection, &guest.monitor_socket_path)?;
+        self.create_parent_dir(connection, &guest.pidfile_path)?;
+        if let Some(qmp_socket_path) = &guest.qmp_socket_path {
+            self.create_parent_dir(connection, qmp_socket_path)?;
+        }
+        if let Some(serial_socket_path) = &guest.serial_socket_path {
+            self.create_parent_dir(connection, serial_socket_path)?;
+        }
+        if let Some(serial_log_path) = &guest.serial_log_path {
+            self.create_parent_dir(connection, serial_log_path)?;
+        }
+        if let Some(guest_agent_socket_path) = &guest.guest_agent_socket_path {
+            self.create_parent_dir(connection, guest_agent_socket_path)?;
+        }
+
+        let seed_path = self.create_cloud_init_seed(connection, guest_id)?;
+        self.start_tpm(connection, guest_id)?;
+        let tpm_state_path = self.get_guest_tpm_state_path(guest_id)?;
+
+        let result = command_macros::command! {
             {connection.execute(QEMU_COMMAND)}
             -name (guest_id)
-            -machine q35,accel=kvm
+            if guest.secure_boot && nvram_path.is_some() {
+                -machine q35,accel=kvm,smm=on
+                -global driver=cfi.pflash01,property=secure,value=on
+            } else {
+                -machine q35,accel=kvm
+            }
+            if let Some(nvram_path) = &nvram_path {
+                -drive "if"=pflash,format=raw,readonly=on,file=(code_path)
+                -drive "if"=pflash,format=qcow2,file=(nvram_path)
+            }
             -cpu (guest.host)
             -m ((guest.memory))M
             -smp ((guest.cores))
             -no-user-config
             -nodefaults
             -daemonize
-            -runas nobody
+            -runas (QEMU_USER)
             -monitor unix:(guest.monitor_socket_path),server,nowait
+            if let Some(qmp_socket_path) = &guest.qmp_socket_path {
+                -qmp unix:(qmp_socket_path),server,nowait
+            }
             -pidfile (guest.pidfile_path)
             -vga std
             -spice port=((guest.spice_port)),disable-ticketing=on
+            if let Some(chardev) = serial_chardev(guest) {
+                -chardev (chardev)
+                -serial chardev:chardev.serial
+            }
+            if let Some(guest_agent_socket_path) = &guest.guest_agent_socket_path {
+                -chardev socket,id=chardev.guest-agent,path=(guest_agent_socket_path),server=on,wait=off
+                -device virtio-serial-pci-non-transitional
+                -device virtserialport,chardev=chardev.guest-agent,name=org.qemu.guest_agent.0
+            }
+            if let Some(tpm_state_path) = &tpm_state_path {
+                -chardev socket,id=chardev.tpm,path=(tpm::socket_path(tpm_state_path))
+                -tpmdev emulator,id=tpm,chardev=chardev.tpm
+                -device tpm-crb,tpmdev=tpm
+            }
             -object iothread,id=iothread1
             -device virtio-scsi-pci-non-transitional,iothread=iothread1
-            for network_interface in &guest.network_interfaces {
-                -device (network_interface.model),netdev=network.(network_interface.tap_name),mac=(network_interface.mac_address)
-                -netdev tap,id=network.(network_interface.tap_name),ifname=(network_interface.tap_name),script=no,downscript=no
+            for (index, network_interface) in guest.network_interfaces.iter().enumerate() {
+                -device (network_device(guest_id, index, network_interface))
+                -netdev (network_backend(guest_id, index, network_interface))
             }
             for (i, disk) in guest.disks.iter().enumerate() {
                 -device (disk.model),drive=drive.(disk.label)
@@ -70,25 +152,291 @@ impl App {
                 -device scsi-cd,drive=drive.cd((i))
                 -drive "if"=none,id=drive.cd((i)),format=raw,media=cdrom,file=(path)
             }
+            if let Some(seed_path) = &seed_path {
+                -device scsi-cd,drive=drive.cidata
+                -drive "if"=none,id=drive.cidata,format=raw,media=cdrom,readonly=on,file=(seed_path)
+            }
             if let Some(path) = floppy_path {
                 -drive "if"=floppy,id=drive.fd0,format=raw,file=fat:floppy:rw:(path)
             }
         }
+        .execute();
+        // swtpm keeps holding its socket, so it must not outlive a guest that failed to start
+        if let Err(error) = result {
+            let _ = self.stop_tpm(connection, guest_id);
+            return Err(error);
+        }
+
+        for (index, network_interface) in guest.network_interfaces.iter().enumerate() {
+            let tap_name = network_interface.tap_name(guest_id, index);
+            self.attach_tap(connection, network_interface, &tap_name)?;
+        }
+
+        command_macros::command! {
+            {connection.execute(CHMOD_COMMAND)} 644 (guest.pidfile_path)
+        }
         .execute()?;
 
-        for network_interface in &guest.network_interfaces {
-            let network = self.get_network(&network_interface.network_id)?;
-            command_macros::command! {
-                {connection.execute(IP_COMMAND)} link set (network_interface.tap_name) master (network.bridge_name) up
+        self.ensure_guest_firewall(connection, guest_id)?;
+
+        Ok(())
+    }
+
+    fn create_cloud_init_seed(
+        &self,
+        connection: &SshConnection,
+        guest_id: &str,
+    ) -> Result<Option<PathBuf>> {
+        let guest = self.get_guest(guest_id)?;
+
+        let cloud_init = match &guest.cloud_init {
+            Some(cloud_init) => cloud_init,
+            None => return Ok(None),
+        };
+
+        let seed_path = self.get_guest_path(
+            guest_id,
+            &cloud_init.seed_path,
+            "cloud_init.seed_path",
+            format!("{guest_id}.seed.iso"),
+        )?;
+
+        // relative paths are resolved against the configuration file
+        let user_data = match &cloud_init.user_data_path {
+            Some(path) => {
+                let path = match self.config_path.parent() {
+                    Some(config_dir) => config_dir.join(path),
+                    None => path.clone(),
+                };
+                std::fs::read_to_string(&path).map_err(|_| ReadUserDataError::new(&path))?
             }
-            .execute()?;
+            None => "#cloud-config\n".to_string(),
+        };
+
+        // cloud-init reads yaml, and json is valid yaml without the quoting
+        // pitfalls
+        let meta_data = serde_json::json!({
+            "instance-id": guest_id,
+            "local-hostname": cloud_init.hostname.as_deref().unwrap_or(guest_id),
+            "public-keys": cloud_init.ssh_authorized_keys,
+        });
+        let network_config = self.get_cloud_init_network_config(guest_id)?;
+
+        let staging_path = command_macros::command! {
+            {connection.execute(MKTEMP_COMMAND)} -d
         }
+        .execute()?;
+        let staging_path = PathBuf::from(staging_path.trim());
+
+        // the staging directory is removed whether the seed could be built or not
+        let result = self.write_cloud_init_seed(
+            connection,
+            &staging_path,
+            &seed_path,
+            [
+                ("user-data", user_data),
+                ("meta-data", serde_json::to_string_pretty(&meta_data)?),
+                (
+                    "network-config",
+                    serde_json::to_string_pretty(&network_config)?,
+                ),
+            ],
+        );
 
         command_macros::command! {
-            {connection.execute(CHMOD_COMMAND)} 644 (guest.pidfile_path)
+            {connection.execute(RM_COMMAND)} -r (staging_path)
+        }
+        .execute()?;
+
+        result?;
+
+        Ok(Some(seed_path))
+    }
+
+    fn write_cloud_init_seed(
+        &self,
+        connection: &SshConnection,
+        staging_path: &Path,
+        seed_path: &Path,
+        files: [(&str, String); 3],
+    ) -> Result<()> {
+        let mut file_paths = Vec::new();
+        for (file_name, contents) in files {
+            let file_path = staging_path.join(file_name);
+            self.write_file(connection, &file_path, contents)?;
+            file_paths.push(file_path);
+        }
+
+        self.create_parent_dir(connection, seed_path)?;
+        command_macros::command! {
+            {connection.execute(GENISOIMAGE_COMMAND)} -quiet -output (seed_path) -volid cidata -joliet -rock
+            [file_paths]
         }
         .execute()?;
 
         Ok(())
     }
+
+    fn get_cloud_init_network_config(&self, guest_id: &str) -> Result<serde_json::Value> {
+        let guest = self.get_guest(guest_id)?;
+
+        let ip_address = guest.ip_address.parse::<Ipv4Addr>().ok();
+
+        let mut ethernets = serde_json::Map::new();
+        for (index, network_interface) in guest.network_interfaces.iter().enumerate() {
+            let network = self.get_network(&network_interface.network_id)?;
+
+            let mut ethernet = serde_json::json!({
+                "match": { "macaddress": network_interface.mac_address(guest_id, index).to_lowercase() },
+            });
+
+            // the guest address is only configured on the network it belongs
+            // to, with the host bridge acting as gateway and resolver where
+            // mima routes and serves dns, other networks are left to whatever
+            // dhcp server runs there
+            let address = network.address.as_deref().and_then(parse_ipv4_network);
+            match (ip_address, address) {
+                (Some(ip_address), Some((address, prefix_length)))
+                    if self.is_in_network(&network_interface.network_id, ip_address) =>
+                {
+                    ethernet["addresses"] =
+                        serde_json::json!([format!("{ip_address}/{prefix_length}")]);
+                    if network.mode == NetworkMode::Nat {
+                        ethernet["routes"] =
+                            serde_json::json!([{ "to": "default", "via": address.to_string() }]);
+                    }
+                    if network.dhcp.is_some() {
+                        ethernet["nameservers"] = serde_json::json!({
+                            "addresses": [address.to_string()],
+                            "search": [network_interface.network_id],
+                        });
+                    }
+                }
+                _ => {
+                    ethernet["dhcp4"] = serde_json::json!(true);
+                }
+            }
+
+            ethernets.insert(format!("nic{index}"), ethernet);
+        }
+
+        Ok(serde_json::json!({
+            "version": 2,
+            "ethernets": ethernets,
+        }))
+    }
+
+    fn check_network_interfaces(&self, guest_id: &str) -> Result<()> {
+        let guest = self.get_guest(guest_id)?;
+
+        for (index, network_interface) in guest.network_interfaces.iter().enumerate() {
+            let mac_address = network_interface.mac_address(guest_id, index);
+            let tap_name = network_interface.tap_name(guest_id, index);
+
+            if !network_interface::is_valid_tap_name(&tap_name) {
+                anyhow::bail!(InvalidTapNameError::new(guest_id, &tap_name));
+            }
+
+            // only virtio devices know about multiple queues, qemu refuses to
+            // start otherwise
+            if network_interface.queues.is_some()
+                && !network_interface.model.starts_with("virtio-net")
+            {
+                anyhow::bail!(UnsupportedMultiqueueError::new(
+                    guest_id,
+                    &network_interface.model
+                ));
+            }
+
+            for (other_guest_id, other_guest) in &self.guests {
+                for (other_index, other_network_interface) in
+                    other_guest.network_interfaces.iter().enumerate()
+                {
+                    if other_guest_id == guest_id && other_index == index {
+                        continue;
+                    }
+
+                    if other_network_interface
+                        .mac_address(other_guest_id, other_index)
+                        .eq_ignore_ascii_case(&mac_address)
+                    {
+                        anyhow::bail!(NetworkInterfaceConflictError::new(
+                            guest_id,
+                            other_guest_id,
+                            &mac_address
+                        ));
+                    }
+                    if other_network_interface.tap_name(other_guest_id, other_index) == tap_name {
+                        anyhow::bail!(NetworkInterfaceConflictError::new(
+                            guest_id,
+                            other_guest_id,
+                            &tap_name
+                        ));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn serial_chardev(guest: &Guest) -> Option<String> {
+    // output is logged even without a socket, the null chardev then drops it
+    let mut chardev = match (&guest.serial_socket_path, &guest.serial_log_path) {
+        (Some(serial_socket_path), _) => format!(
+            "socket,id=chardev.serial,path={path},server=on,wait=off",
+            path = serial_socket_path.display(),
+        ),
+        (None, Some(_)) => "null,id=chardev.serial".to_string(),
+        (None, None) => return None,
+    };
+    if let Some(serial_log_path) = &guest.serial_log_path {
+        chardev.push_str(&format!(
+            ",logfile={path},logappend=on",
+            path = serial_log_path.display(),
+        ));
+    }
+
+    Some(chardev)
+}
+
+fn network_device(guest_id: &str, index: usize, network_interface: &NetworkInterface) -> String {
+    let mut device = format!(
+        "{model},netdev=network.{tap_name},mac={mac_address}",
+        mac_address = network_interface.mac_address(guest_id, index),
+        model = network_interface.model,
+        tap_name = network_interface.tap_name(guest_id, index),
+    );
+    // every queue pair needs its own tx and rx vectors, plus one for
+    // configuration changes and one for the control queue
+    if let Some(queues) = network_interface.queues {
+        device.push_str(&format!(",mq=on,vectors={}", 2 * queues + 2));
+    }
+    if let Some(mtu) = network_interface.mtu {
+        device.push_str(&format!(",host_mtu={mtu}"));
+    }
+    if let Some(rombar) = network_interface.rombar {
+        device.push_str(&format!(",rombar={}", u8::from(rombar)));
+    }
+    if let Some(bootindex) = network_interface.bootindex {
+        device.push_str(&format!(",bootindex={bootindex}"));
+    }
+
+    device
+}
+
+fn network_backend(guest_id: &str, index: usize, network_interface: &NetworkInterface) -> String {
+    let mut backend = format!(
+        "tap,id=network.{tap_name},ifname={tap_name},script=no,downscript=no",
+        tap_name = network_interface.tap_name(guest_id, index),
+    );
+    if network_interface.vhost {
+        backend.push_str(",vhost=on");
+    }
+    if let Some(queues) = network_interface.queues {
+        backend.push_str(&format!(",queues={queues}"));
+    }
+
+    backend
 }
@@ -1,12 +1,8 @@
+use crate::app::SshConnection;
 use crate::app::PKILL_COMMAND;
-use crate::app::SOCAT_COMMAND;
 use crate::command::Execute;
-use crate::errors::MonitorCommandError;
-use crate::errors::ProcessExecutionError;
 use crate::App;
 use anyhow::Result;
-use std::io::Write;
-use std::process::Stdio;
 use std::time::Duration;
 
 impl App {
@@ -18,33 +14,51 @@ impl App {
 
         let guest_id = guest_id.as_ref();
 
-        let guest = self.get_guest(guest_id)?;
+        self.get_guest(guest_id)?;
 
-        if !self.is_booted(&connection, guest_id)? {
+        if !self.is_booted(connection, guest_id)? {
             return Ok(());
         }
 
+        self.shutdown_guest(connection, guest_id, wait, force)?;
+
+        // qemu removes its taps on exit, but a crashed or killed process
+        // may leave them behind
+        self.remove_stale_taps(connection, guest_id)?;
+        self.stop_tpm(connection, guest_id)?;
+
+        self.remove_guest_firewall(connection, guest_id)?;
+
+        Ok(())
+    }
+
+    fn shutdown_guest(
+        &self,
+        connection: &SshConnection,
+        guest_id: &str,
+        wait: u64,
+        force: bool,
+    ) -> Result<()> {
+        let guest = self.get_guest(guest_id)?;
+
         if !force {
-            let mut command = command_macros::command! {
-                {connection.execute(SOCAT_COMMAND)} - UNIX-CONNECT:(guest.monitor_socket_path)
-            };
-            let monitor = command
-                .stdin(Stdio::piped())
-                .stderr(Stdio::null())
-                .stdout(Stdio::null())
-                .spawn()
-                .map_err(|_| ProcessExecutionError::new(&command))?;
-            if let Some(mut stdin) = monitor.stdin {
-                writeln!(stdin, "system_powerdown").map_err(|_| {
-                    MonitorCommandError::new(&guest.monitor_socket_path, "system_powerdown")
-                })?;
+            // the agent shuts the guest down even when it ignores acpi events
+            if self.is_guest_agent_running(connection, guest_id)? {
+                self.send_guest_agent_command(
+                    connection,
+                    guest_id,
+                    "guest-shutdown",
+                    serde_json::json!({}),
+                )?;
+            } else {
+                self.execute_monitor_command(connection, guest_id, "system_powerdown")?;
             }
 
             let delay = Duration::from_millis(1000);
             for _ in 0..wait {
                 std::thread::sleep(delay);
 
-                if !self.is_booted(&connection, guest_id)? {
+                if !self.is_booted(connection, guest_id)? {
                     return Ok(());
                 }
             }
@@ -58,7 +72,7 @@ impl App {
         let delay = Duration::from_millis(1000);
         std::thread::sleep(delay);
 
-        if self.is_booted(&connection, guest_id)? {
+        if self.is_booted(connection, guest_id)? {
             command_macros::command! {
                 {connection.execute(PKILL_COMMAND)} -9 --full --pidfile (guest.pidfile_path) qemu
             }
@@ -0,0 +1,18 @@
+use super::guest::Guest;
+use super::network::Network;
+use super::ssh_settings::SshSettings;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+#[derive(Deserialize)]
+pub struct Config {
+    pub host: String,
+    #[serde(default)]
+    pub ensure_networks: bool,
+    #[serde(default)]
+    pub ssh: SshSettings,
+    #[serde(default)]
+    pub guest_ssh: SshSettings,
+    pub guests: BTreeMap<String, Guest>,
+    pub networks: BTreeMap<String, Network>,
+}
@@ -0,0 +1,8 @@
+use serde::Deserialize;
+use std::path::PathBuf;
+
+#[derive(Deserialize)]
+pub struct Dhcp {
+    pub config_path: PathBuf,
+    pub pidfile_path: PathBuf,
+}
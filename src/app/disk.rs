@@ -1,7 +1,8 @@
 use serde::Deserialize;
+use serde::Serialize;
 use std::path::PathBuf;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
 pub struct Disk {
     pub label: String,
     pub path: PathBuf,
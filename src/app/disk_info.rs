@@ -0,0 +1,9 @@
+use super::snapshot::Snapshot;
+use std::collections::HashMap;
+
+pub struct DiskInfo {
+    pub virtual_size: u64,
+    pub actual_size: u64,
+    pub bitmaps: Vec<String>,
+    pub snapshots: HashMap<String, Snapshot>,
+}
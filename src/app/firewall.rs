@@ -0,0 +1,144 @@
+use super::network::Network;
+use super::port_forward::PortForward;
+
+pub fn network_table<T>(network_id: T) -> String
+where
+    T: AsRef<str>,
+{
+    format!(
+        "mima-network-{network_id}",
+        network_id = network_id.as_ref()
+    )
+}
+
+pub fn guest_table<T>(guest_id: T) -> String
+where
+    T: AsRef<str>,
+{
+    format!("mima-guest-{guest_id}", guest_id = guest_id.as_ref())
+}
+
+pub fn delete_table<T, U>(family: T, table: U) -> String
+where
+    T: AsRef<str>,
+    U: AsRef<str>,
+{
+    let family = family.as_ref();
+    let table = table.as_ref();
+
+    // declaring the table first makes the deletion succeed when it does not exist yet
+    format!("table {family} {table} {{}}\ndelete table {family} {table}\n")
+}
+
+pub fn isolated_network_ruleset(network_id: &str, network: &Network) -> String {
+    let table = network_table(network_id);
+    let bridge_name = &network.bridge_name;
+
+    let mut ruleset = delete_table("ip", &table);
+    ruleset.push_str(&format!("table ip {table} {{\n"));
+    ruleset.push_str("    chain forward {\n");
+    ruleset.push_str("        type filter hook forward priority 0; policy accept;\n");
+    ruleset.push_str(&format!("        iifname \"{bridge_name}\" drop\n"));
+    ruleset.push_str(&format!("        oifname \"{bridge_name}\" drop\n"));
+    ruleset.push_str("    }\n");
+    ruleset.push_str("}\n");
+
+    ruleset
+}
+
+pub fn nat_network_ruleset(network_id: &str, network: &Network, subnet: &str) -> String {
+    let table = network_table(network_id);
+    let bridge_name = &network.bridge_name;
+
+    // forwarded ports are let through, everything else has to be initiated
+    // by the guests
+    let mut ruleset = delete_table("ip", &table);
+    ruleset.push_str(&format!("table ip {table} {{\n"));
+    ruleset.push_str("    chain forward {\n");
+    ruleset.push_str("        type filter hook forward priority 0; policy accept;\n");
+    ruleset.push_str(&format!("        iifname \"{bridge_name}\" accept\n"));
+    ruleset.push_str(&format!(
+        "        oifname \"{bridge_name}\" ct state established,related accept\n"
+    ));
+    ruleset.push_str(&format!(
+        "        oifname \"{bridge_name}\" ct status dnat accept\n"
+    ));
+    ruleset.push_str(&format!("        oifname \"{bridge_name}\" drop\n"));
+    ruleset.push_str("    }\n");
+    ruleset.push_str("    chain postrouting {\n");
+    ruleset.push_str("        type nat hook postrouting priority 100; policy accept;\n");
+    ruleset.push_str(&format!(
+        "        ip saddr {subnet} ip daddr != {subnet} masquerade\n"
+    ));
+    ruleset.push_str("    }\n");
+    ruleset.push_str("}\n");
+
+    ruleset
+}
+
+pub fn port_forward_ruleset(
+    guest_id: &str,
+    ip_address: &str,
+    port_forwards: &[PortForward],
+) -> String {
+    let table = guest_table(guest_id);
+
+    let mut ruleset = delete_table("ip", &table);
+    ruleset.push_str(&format!("table ip {table} {{\n"));
+    ruleset.push_str("    chain prerouting {\n");
+    ruleset.push_str("        type nat hook prerouting priority -100; policy accept;\n");
+    for port_forward in port_forwards {
+        ruleset.push_str(&format!(
+            "        fib daddr type local {protocol} dport {host_port} dnat to {ip_address}:{guest_port}\n",
+            guest_port = port_forward.guest_port,
+            host_port = port_forward.host_port,
+            protocol = port_forward.protocol.as_str(),
+        ));
+    }
+    ruleset.push_str("    }\n");
+    ruleset.push_str("}\n");
+
+    ruleset
+}
+
+// only traffic carrying the declared MAC address leaves the taps, and on
+// interfaces where the guest address applies also only its IPv4 address,
+// except for DHCP discovery, while IPv6 is dropped there altogether
+pub fn anti_spoofing_ruleset(
+    guest_id: &str,
+    ip_address: &str,
+    network_interfaces: &[(String, String, bool)],
+) -> String {
+    let table = guest_table(guest_id);
+
+    let mut ruleset = delete_table("bridge", &table);
+    ruleset.push_str(&format!("table bridge {table} {{\n"));
+    ruleset.push_str("    chain prerouting {\n");
+    ruleset.push_str("        type filter hook prerouting priority -200; policy accept;\n");
+    for (tap_name, mac_address, restrict_ip_address) in network_interfaces {
+        ruleset.push_str(&format!(
+            "        iifname \"{tap_name}\" ether saddr != {mac_address} drop\n"
+        ));
+        ruleset.push_str(&format!(
+            "        iifname \"{tap_name}\" arp saddr ether != {mac_address} drop\n"
+        ));
+        if *restrict_ip_address {
+            ruleset.push_str(&format!(
+                "        iifname \"{tap_name}\" arp saddr ip != {ip_address} drop\n"
+            ));
+            ruleset.push_str(&format!(
+                "        iifname \"{tap_name}\" ip saddr 0.0.0.0 udp dport 67 accept\n"
+            ));
+            ruleset.push_str(&format!(
+                "        iifname \"{tap_name}\" ip saddr != {ip_address} drop\n"
+            ));
+            ruleset.push_str(&format!(
+                "        iifname \"{tap_name}\" ether type ip6 drop\n"
+            ));
+        }
+    }
+    ruleset.push_str("    }\n");
+    ruleset.push_str("}\n");
+
+    ruleset
+}
@@ -1,9 +1,14 @@
+use super::cloud_init::CloudInit;
 use super::disk::Disk;
+use super::firmware::Firmware;
 use super::network_interface::NetworkInterface;
+use super::port_forward::PortForward;
+use super::ssh_settings::SshSettings;
 use serde::Deserialize;
+use serde::Serialize;
 use std::path::PathBuf;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
 pub struct Guest {
     pub description: String,
     pub ip_address: String,
@@ -12,9 +17,35 @@ pub struct Guest {
     pub spice_port: i64,
     #[serde(default = "default_host")]
     pub host: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ssh: Option<SshSettings>,
     pub monitor_socket_path: PathBuf,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub qmp_socket_path: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub serial_socket_path: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub serial_log_path: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub guest_agent_socket_path: Option<PathBuf>,
     pub pidfile_path: PathBuf,
+    #[serde(default, skip_serializing_if = "Firmware::is_bios")]
+    pub firmware: Firmware,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub secure_boot: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nvram_path: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub tpm: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tpm_state_path: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cloud_init: Option<CloudInit>,
     pub network_interfaces: Vec<NetworkInterface>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub port_forwards: Vec<PortForward>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub anti_spoofing: bool,
     pub disks: Vec<Disk>,
 }
 
@@ -0,0 +1,21 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Clone, Copy, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HostKeyChecking {
+    Yes,
+    AcceptNew,
+    #[default]
+    No,
+}
+
+impl HostKeyChecking {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Yes => "yes",
+            Self::AcceptNew => "accept-new",
+            Self::No => "no",
+        }
+    }
+}
@@ -0,0 +1,5 @@
+pub struct LinkInfo {
+    pub state: String,
+    pub master: Option<String>,
+    pub addresses: Vec<String>,
+}
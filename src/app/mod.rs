@@ -1,43 +1,113 @@
+mod backup;
+mod bundle;
+mod cloud_init;
 mod commands;
+mod config;
+mod dhcp;
 mod disk;
+mod disk_info;
+mod firewall;
+mod firmware;
 mod guest;
+mod host_key_checking;
+mod link_info;
 mod network;
 mod network_interface;
+mod network_mode;
+mod port_forward;
+mod protocol;
 mod snapshot;
 mod ssh_connection;
+mod ssh_settings;
+mod tpm;
 
 use crate::command::Execute;
+use crate::errors::CommandExecutionFailedError;
+use crate::errors::GuestAgentCommandError;
+use crate::errors::InvalidNetworkAddressError;
+use crate::errors::MissingGuestAgentSocketError;
+use crate::errors::MissingGuestPathError;
+use crate::errors::MissingNvramPathError;
+use crate::errors::MissingQmpSocketError;
+use crate::errors::MissingTpmStatePathError;
+use crate::errors::MonitorCommandError;
 use crate::errors::ParseConfigurationError;
 use crate::errors::ProcessExecutionError;
 use crate::errors::ReadConfigurationError;
 use crate::errors::UnknownGuestError;
 use crate::errors::UnknownNetworkError;
 use anyhow::Result;
+use config::Config;
 use disk::Disk;
+use disk_info::DiskInfo;
+use firmware::Firmware;
 use guest::Guest;
+use link_info::LinkInfo;
 use network::Network;
+use network_interface::NetworkInterface;
+use network_mode::NetworkMode;
+use serde::de::DeserializeOwned;
+use serde::de::IgnoredAny;
 use serde::Deserialize;
 use snapshot::Snapshot;
 use ssh_connection::SshConnection;
+use ssh_settings::SshSettings;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Write;
+use std::net::Ipv4Addr;
 use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
 use std::process::Stdio;
 use std::time::Duration;
+use std::time::SystemTime;
 
+const BRIDGE_COMMAND: &str = "bridge";
+const CAT_COMMAND: &str = "cat";
 const CHMOD_COMMAND: &str = "chmod";
+const CHOWN_COMMAND: &str = "chown";
+const DF_COMMAND: &str = "df";
+const DNSMASQ_COMMAND: &str = "dnsmasq";
+const DU_COMMAND: &str = "du";
+const GENISOIMAGE_COMMAND: &str = "genisoimage";
 const IP_COMMAND: &str = "ip";
+const LN_COMMAND: &str = "ln";
+const LS_COMMAND: &str = "ls";
 const MKDIR_COMMAND: &str = "mkdir";
+const MKTEMP_COMMAND: &str = "mktemp";
+const MV_COMMAND: &str = "mv";
+const NFT_COMMAND: &str = "nft";
 const PGREP_COMMMAND: &str = "pgrep";
 const PKILL_COMMAND: &str = "pkill";
+const RM_COMMAND: &str = "rm";
+const SH_COMMAND: &str = "sh";
 const SOCAT_COMMAND: &str = "socat";
+const SWTPM_COMMAND: &str = "swtpm";
+const SYSCTL_COMMAND: &str = "sysctl";
+const TAIL_COMMAND: &str = "tail";
+const TAR_COMMAND: &str = "tar";
+const TEE_COMMAND: &str = "tee";
 const TEST_COMMAND: &str = "test";
+const TIMEOUT_COMMAND: &str = "timeout";
+const TRUE_COMMAND: &str = "true";
 const QEMU_COMMAND: &str = "qemu-system-x86_64";
 const QEMU_IMG_COMMAND: &str = "qemu-img";
+const QEMU_USER: &str = "nobody";
+const GUEST_AGENT_PING_TIMEOUT: u64 = 1;
+const GUEST_AGENT_TIMEOUT: u64 = 10;
+const OVMF_CODE_PATH: &str = "/usr/share/OVMF/OVMF_CODE_4M.fd";
+const OVMF_VARS_PATH: &str = "/usr/share/OVMF/OVMF_VARS_4M.fd";
+const OVMF_SECURE_BOOT_CODE_PATH: &str = "/usr/share/OVMF/OVMF_CODE_4M.secboot.fd";
+const OVMF_SECURE_BOOT_VARS_PATH: &str = "/usr/share/OVMF/OVMF_VARS_4M.ms.fd";
 
-#[derive(Deserialize)]
 pub struct App {
-    host: String,
+    config_path: PathBuf,
+    ensure_networks: bool,
+    guest_ssh: SshSettings,
+    host_connection: SshConnection,
     guests: BTreeMap<String, Guest>,
     networks: BTreeMap<String, Network>,
 }
@@ -52,18 +122,84 @@ impl App {
         let config =
             std::fs::read_to_string(path).map_err(|_| ReadConfigurationError::new(path))?;
 
-        let app =
-            toml::from_str::<Self>(&config).map_err(|_| ParseConfigurationError::new(path))?;
+        let config =
+            toml::from_str::<Config>(&config).map_err(|_| ParseConfigurationError::new(path))?;
+
+        // the host connection lives as long as the app, so every remote
+        // command of an invocation goes through the same session
+        let host_connection = SshConnection::new(&config.host, config.ssh, true).multiplexed();
 
-        Ok(app)
+        Ok(Self {
+            config_path: path.to_owned(),
+            ensure_networks: config.ensure_networks,
+            guest_ssh: config.guest_ssh,
+            host_connection,
+            guests: config.guests,
+            networks: config.networks,
+        })
     }
 
-    fn get_disk_snapshots<T>(
+    fn get_dhcp_config<T>(&self, network_id: T) -> Result<String>
+    where
+        T: AsRef<str>,
+    {
+        let network_id = network_id.as_ref();
+
+        let network = self.get_network(network_id)?;
+
+        let (subnet, netmask, _) = self.get_network_subnet(network_id)?;
+
+        let mut config = String::new();
+        config.push_str("# generated by mima, do not edit\n");
+        config.push_str(&format!("interface={}\n", network.bridge_name));
+        config.push_str("bind-interfaces\n");
+        config.push_str("except-interface=lo\n");
+        if let Some(dhcp) = &network.dhcp {
+            config.push_str(&format!("pid-file={}\n", dhcp.pidfile_path.display()));
+        }
+        config.push_str("leasefile-ro\n");
+        config.push_str(&format!("domain={network_id}\n"));
+        config.push_str(&format!("local=/{network_id}/\n"));
+        config.push_str(&format!("dhcp-range={subnet},static,{netmask}\n"));
+        for (guest_id, guest) in &self.guests {
+            // guests only get a lease on the network their address belongs to
+            let ip_address = match guest.ip_address.parse::<Ipv4Addr>() {
+                Ok(ip_address) => ip_address,
+                Err(_) => continue,
+            };
+            if u32::from(ip_address) & u32::from(netmask) != u32::from(subnet) {
+                continue;
+            }
+
+            let mac_addresses: Vec<_> = guest
+                .network_interfaces
+                .iter()
+                .enumerate()
+                .filter(|(_, network_interface)| network_interface.network_id == network_id)
+                .map(|(index, network_interface)| network_interface.mac_address(guest_id, index))
+                .collect();
+            if mac_addresses.is_empty() {
+                continue;
+            }
+
+            config.push_str(&format!(
+                "dhcp-host={mac_addresses},{ip_address},{guest_id}\n",
+                mac_addresses = mac_addresses.join(","),
+            ));
+            config.push_str(&format!(
+                "host-record={guest_id}.{network_id},{ip_address}\n"
+            ));
+        }
+
+        Ok(config)
+    }
+
+    fn get_disk_info<T>(
         &self,
         connection: &SshConnection,
         guest_id: T,
         disk_id: usize,
-    ) -> Result<HashMap<String, Snapshot>>
+    ) -> Result<DiskInfo>
     where
         T: AsRef<str>,
     {
@@ -71,9 +207,39 @@ impl App {
         // so we can use unwrap here
         let disk = self.get_guest_disks(guest_id)?.get(disk_id).unwrap();
 
+        self.get_image_info(connection, &disk.path)
+    }
+
+    fn get_image_info<T>(&self, connection: &SshConnection, path: T) -> Result<DiskInfo>
+    where
+        T: AsRef<Path>,
+    {
+        let path = path.as_ref();
+
         #[derive(Deserialize)]
         struct QemuImgInfo {
+            #[serde(rename = "virtual-size")]
+            virtual_size: u64,
+            #[serde(default, rename = "actual-size")]
+            actual_size: u64,
             snapshots: Option<Vec<QemuImgSnapshot>>,
+            #[serde(rename = "format-specific")]
+            format_specific: Option<QemuImgFormatSpecific>,
+        }
+
+        #[derive(Deserialize)]
+        struct QemuImgFormatSpecific {
+            data: Option<QemuImgFormatSpecificData>,
+        }
+
+        #[derive(Deserialize)]
+        struct QemuImgFormatSpecificData {
+            bitmaps: Option<Vec<QemuImgBitmap>>,
+        }
+
+        #[derive(Deserialize)]
+        struct QemuImgBitmap {
+            name: String,
         }
 
         #[derive(Deserialize)]
@@ -83,27 +249,60 @@ impl App {
             timestamp_sec: u64,
             #[serde(rename = "date-nsec")]
             timestamp_nsec: u32,
+            #[serde(rename = "vm-state-size")]
+            vm_state_size: u64,
         }
 
-        let snapshots = command_macros::command! {
-            {connection.execute(QEMU_IMG_COMMAND)} info --force-share --output=json (disk.path)
+        let info = command_macros::command! {
+            {connection.execute(QEMU_IMG_COMMAND)} info --force-share --output=json (path)
         }
-        .execute_and_parse_json_output::<QemuImgInfo>()?
-        .snapshots
-        .unwrap_or_default()
-        .into_iter()
-        .map(|snapshot| {
-            (
-                snapshot.name.clone(),
-                Snapshot {
-                    id: snapshot.name.clone(),
-                    timestamp: Duration::new(snapshot.timestamp_sec, snapshot.timestamp_nsec),
-                },
-            )
+        .execute_and_parse_json_output::<QemuImgInfo>()?;
+
+        let snapshots = info
+            .snapshots
+            .unwrap_or_default()
+            .into_iter()
+            .map(|snapshot| {
+                (
+                    snapshot.name.clone(),
+                    Snapshot {
+                        id: snapshot.name.clone(),
+                        timestamp: Duration::new(snapshot.timestamp_sec, snapshot.timestamp_nsec),
+                        vm_state_size: snapshot.vm_state_size,
+                    },
+                )
+            })
+            .collect();
+
+        let bitmaps = info
+            .format_specific
+            .and_then(|format_specific| format_specific.data)
+            .and_then(|data| data.bitmaps)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|bitmap| bitmap.name)
+            .collect();
+
+        Ok(DiskInfo {
+            virtual_size: info.virtual_size,
+            actual_size: info.actual_size,
+            bitmaps,
+            snapshots,
         })
-        .collect();
+    }
 
-        Ok(snapshots)
+    fn get_disk_snapshots<T>(
+        &self,
+        connection: &SshConnection,
+        guest_id: T,
+        disk_id: usize,
+    ) -> Result<HashMap<String, Snapshot>>
+    where
+        T: AsRef<str>,
+    {
+        let info = self.get_disk_info(connection, guest_id, disk_id)?;
+
+        Ok(info.snapshots)
     }
 
     fn get_guest<T>(&self, guest_id: T) -> Result<&Guest>
@@ -127,6 +326,120 @@ impl App {
         Ok(&guest.disks)
     }
 
+    fn get_guest_path<T, U, V>(
+        &self,
+        guest_id: T,
+        path: &Option<PathBuf>,
+        setting: U,
+        file_name: V,
+    ) -> Result<PathBuf>
+    where
+        T: AsRef<str>,
+        U: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let guest_id = guest_id.as_ref();
+
+        let guest = self.get_guest(guest_id)?;
+
+        match guest_path(guest, path, file_name) {
+            Some(path) => Ok(path),
+            None => anyhow::bail!(MissingGuestPathError::new(guest_id, setting)),
+        }
+    }
+
+    fn get_guest_nvram_path<T>(&self, guest_id: T) -> Result<Option<PathBuf>>
+    where
+        T: AsRef<str>,
+    {
+        let guest_id = guest_id.as_ref();
+
+        let guest = self.get_guest(guest_id)?;
+
+        guest_nvram_path(guest_id, guest)
+    }
+
+    fn get_guest_tpm_state_path<T>(&self, guest_id: T) -> Result<Option<PathBuf>>
+    where
+        T: AsRef<str>,
+    {
+        let guest_id = guest_id.as_ref();
+
+        let guest = self.get_guest(guest_id)?;
+
+        if !guest.tpm {
+            return Ok(None);
+        }
+
+        match guest_path(guest, &guest.tpm_state_path, format!("{guest_id}.tpm")) {
+            Some(tpm_state_path) => Ok(Some(tpm_state_path)),
+            None => anyhow::bail!(MissingTpmStatePathError::new(guest_id)),
+        }
+    }
+
+    fn get_existing_guest_nvram_path<T>(
+        &self,
+        connection: &SshConnection,
+        guest_id: T,
+    ) -> Result<Option<PathBuf>>
+    where
+        T: AsRef<str>,
+    {
+        match self.get_guest_nvram_path(guest_id)? {
+            Some(nvram_path) if self.exists(connection, &nvram_path)? => Ok(Some(nvram_path)),
+            _ => Ok(None),
+        }
+    }
+
+    fn get_bridge_ports<T>(&self, connection: &SshConnection, bridge_name: T) -> Result<Vec<String>>
+    where
+        T: AsRef<str>,
+    {
+        let bridge_name = bridge_name.as_ref();
+
+        #[derive(Deserialize)]
+        struct IpLink {
+            ifname: String,
+        }
+
+        let links = command_macros::command! {
+            {connection.execute(IP_COMMAND)} -json link show master (bridge_name)
+        }
+        .execute_and_parse_json_output::<Vec<IpLink>>()?;
+
+        Ok(links.into_iter().map(|link| link.ifname).collect())
+    }
+
+    fn get_guest_backups<T, U>(
+        &self,
+        connection: &SshConnection,
+        guest_id: T,
+        path: U,
+    ) -> Result<Vec<String>>
+    where
+        T: AsRef<str>,
+        U: AsRef<Path>,
+    {
+        let guest_id = guest_id.as_ref();
+        let path = backup::guest_path(path, guest_id);
+
+        if !self.exists(connection, &path)? {
+            return Ok(Vec::new());
+        }
+
+        let mut backups: Vec<_> = command_macros::command! {
+            {connection.execute(LS_COMMAND)} -1 (path)
+        }
+        .execute()?
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect();
+        backups.sort();
+
+        Ok(backups)
+    }
+
     fn get_guest_snapshots<T>(
         &self,
         connection: &SshConnection,
@@ -155,7 +468,11 @@ impl App {
                         } else {
                             disk_snapshot.timestamp - snapshot.timestamp
                         };
-                        difference.as_secs() < 300 // 5 minutes
+                        let common = difference.as_secs() < 300; // 5 minutes
+                        if common {
+                            snapshot.vm_state_size += disk_snapshot.vm_state_size;
+                        }
+                        common
                     } else {
                         false
                     }
@@ -174,11 +491,96 @@ impl App {
 
         let guest = self.get_guest(guest_id)?;
 
-        Ok(SshConnection::new(&guest.ip_address))
+        // the host settings are not applied to guests, which usually have
+        // their own users and keys
+        let settings = match &guest.ssh {
+            Some(settings) => settings.or(&self.guest_ssh),
+            None => self.guest_ssh.clone(),
+        };
+
+        Ok(SshConnection::new(&guest.ip_address, settings, false))
+    }
+
+    fn get_host_ssh_connection(&self) -> &SshConnection {
+        &self.host_connection
+    }
+
+    fn get_link_info<T>(&self, connection: &SshConnection, name: T) -> Result<Option<LinkInfo>>
+    where
+        T: AsRef<str>,
+    {
+        let name = name.as_ref();
+
+        if !self.link_exists(connection, name)? {
+            return Ok(None);
+        }
+
+        #[derive(Deserialize)]
+        struct IpAddress {
+            operstate: String,
+            #[serde(default)]
+            master: Option<String>,
+            #[serde(default)]
+            addr_info: Vec<IpAddressInfo>,
+        }
+
+        #[derive(Deserialize)]
+        struct IpAddressInfo {
+            local: String,
+            prefixlen: u8,
+        }
+
+        let addresses = command_macros::command! {
+            {connection.execute(IP_COMMAND)} -json address show dev (name)
+        }
+        .execute_and_parse_json_output::<Vec<IpAddress>>()?;
+
+        Ok(addresses.into_iter().next().map(|address| LinkInfo {
+            state: address.operstate,
+            master: address.master,
+            addresses: address
+                .addr_info
+                .into_iter()
+                .map(|info| {
+                    format!(
+                        "{local}/{prefixlen}",
+                        local = info.local,
+                        prefixlen = info.prefixlen
+                    )
+                })
+                .collect(),
+        }))
+    }
+
+    fn get_network_subnet<T>(&self, network_id: T) -> Result<(Ipv4Addr, Ipv4Addr, u32)>
+    where
+        T: AsRef<str>,
+    {
+        let network_id = network_id.as_ref();
+
+        let network = self.get_network(network_id)?;
+
+        let (address, prefix_length) = network
+            .address
+            .as_deref()
+            .and_then(parse_ipv4_network)
+            .ok_or_else(|| InvalidNetworkAddressError::new(network_id))?;
+        let netmask = Ipv4Addr::from(u32::MAX.checked_shl(32 - prefix_length).unwrap_or(0));
+        let subnet = Ipv4Addr::from(u32::from(address) & u32::from(netmask));
+
+        Ok((subnet, netmask, prefix_length))
     }
 
-    fn get_host_ssh_connection(&self) -> SshConnection {
-        SshConnection::new(&self.host)
+    fn is_in_network<T>(&self, network_id: T, ip_address: Ipv4Addr) -> bool
+    where
+        T: AsRef<str>,
+    {
+        match self.get_network_subnet(network_id) {
+            Ok((subnet, netmask, _)) => {
+                u32::from(ip_address) & u32::from(netmask) == u32::from(subnet)
+            }
+            Err(_) => false,
+        }
     }
 
     fn get_network<T>(&self, network_id: T) -> Result<&Network>
@@ -193,57 +595,892 @@ impl App {
         }
     }
 
-    fn exists<T>(&self, connection: &SshConnection, path: T) -> Result<bool>
+    fn ensure_network<T>(&self, connection: &SshConnection, network_id: T) -> Result<()>
     where
-        T: AsRef<Path>,
+        T: AsRef<str>,
     {
-        let path = path.as_ref();
+        let network_id = network_id.as_ref();
 
-        let status = command_macros::command! {
-            {connection.execute(TEST_COMMAND)} -e (path)
+        let network = self.get_network(network_id)?;
+
+        if !self.link_exists(connection, &network.bridge_name)? {
+            command_macros::command! {
+                {connection.execute(IP_COMMAND)} link add name (network.bridge_name) type bridge
+            }
+            .execute()?;
         }
-        .stdout(Stdio::null())
-        .stderr(Stdio::null())
-        .status();
 
-        if let Ok(status) = status {
-            Ok(status.success())
-        } else {
-            Ok(false)
+        // the bridge only forwards frames as large as its own mtu, so it has
+        // to keep up with the largest one configured for any of its taps
+        let mtu = self
+            .guests
+            .values()
+            .flat_map(|guest| &guest.network_interfaces)
+            .filter(|network_interface| network_interface.network_id == network_id)
+            .filter_map(|network_interface| network_interface.mtu)
+            .max();
+        if let Some(mtu) = mtu {
+            command_macros::command! {
+                {connection.execute(IP_COMMAND)} link set (network.bridge_name) mtu ((mtu))
+            }
+            .execute()?;
+        }
+
+        if let Some(address) = &network.address {
+            command_macros::command! {
+                {connection.execute(IP_COMMAND)} address replace (address) dev (network.bridge_name)
+            }
+            .execute()?;
+        }
+
+        command_macros::command! {
+            {connection.execute(IP_COMMAND)} link set (network.bridge_name) up
         }
+        .execute()?;
+
+        self.ensure_network_firewall(connection, network_id)?;
+        self.ensure_dhcp(connection, network_id)?;
+
+        Ok(())
     }
 
-    fn is_booted<T>(&self, connection: &SshConnection, guest_id: T) -> Result<bool>
+    fn ensure_network_firewall<T>(&self, connection: &SshConnection, network_id: T) -> Result<()>
     where
         T: AsRef<str>,
     {
-        let guest = self.get_guest(guest_id)?;
+        let network_id = network_id.as_ref();
 
-        let mut command = command_macros::command! {
-            {connection.execute(PGREP_COMMMAND)} --full --pidfile (guest.pidfile_path) qemu
+        let network = self.get_network(network_id)?;
+
+        let ruleset = match network.mode {
+            NetworkMode::Isolated => firewall::isolated_network_ruleset(network_id, network),
+            NetworkMode::Nat => {
+                let (subnet, _, prefix_length) = self.get_network_subnet(network_id)?;
+                let subnet = format!("{subnet}/{prefix_length}");
+                self.enable_ip_forwarding(connection)?;
+                firewall::nat_network_ruleset(network_id, network, &subnet)
+            }
+            NetworkMode::Bridged => return Ok(()),
         };
-        let status = command
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .status()
-            .map_err(|_| ProcessExecutionError::new(&command))?;
+        self.load_firewall_ruleset(connection, ruleset)?;
 
-        Ok(status.success())
+        Ok(())
     }
 
-    fn create_parent_dir<T>(&self, connection: &SshConnection, path: T) -> Result<()>
+    fn ensure_guest_firewall<T>(&self, connection: &SshConnection, guest_id: T) -> Result<()>
     where
-        T: AsRef<Path>,
+        T: AsRef<str>,
     {
-        let path = path.as_ref();
+        let guest_id = guest_id.as_ref();
 
-        if let Some(parent_path) = path.parent() {
+        let guest = self.get_guest(guest_id)?;
+
+        let mut ruleset = String::new();
+        if !guest.port_forwards.is_empty() {
+            self.enable_ip_forwarding(connection)?;
+            ruleset.push_str(&firewall::port_forward_ruleset(
+                guest_id,
+                &guest.ip_address,
+                &guest.port_forwards,
+            ));
+        }
+        if guest.anti_spoofing {
+            let ip_address = guest.ip_address.parse::<Ipv4Addr>().ok();
+            let mut network_interfaces = Vec::new();
+            for (index, network_interface) in guest.network_interfaces.iter().enumerate() {
+                // the guest address is only enforced on the network it belongs to
+                let restrict_ip_address = ip_address.map_or(false, |ip_address| {
+                    self.is_in_network(&network_interface.network_id, ip_address)
+                });
+                network_interfaces.push((
+                    network_interface.tap_name(guest_id, index),
+                    network_interface.mac_address(guest_id, index),
+                    restrict_ip_address,
+                ));
+            }
+            ruleset.push_str(&firewall::anti_spoofing_ruleset(
+                guest_id,
+                &guest.ip_address,
+                &network_interfaces,
+            ));
+        }
+        if ruleset.is_empty() {
+            return Ok(());
+        }
+
+        self.load_firewall_ruleset(connection, ruleset)?;
+
+        Ok(())
+    }
+
+    fn remove_guest_firewall<T>(&self, connection: &SshConnection, guest_id: T) -> Result<()>
+    where
+        T: AsRef<str>,
+    {
+        let guest_id = guest_id.as_ref();
+
+        let guest = self.get_guest(guest_id)?;
+
+        // hosts without nftables run guests that use neither feature
+        if guest.port_forwards.is_empty() && !guest.anti_spoofing {
+            return Ok(());
+        }
+
+        // either table may be left over from before the configuration changed
+        let mut ruleset = firewall::delete_table("ip", firewall::guest_table(guest_id));
+        ruleset.push_str(&firewall::delete_table(
+            "bridge",
+            firewall::guest_table(guest_id),
+        ));
+
+        self.load_firewall_ruleset(connection, ruleset)?;
+
+        Ok(())
+    }
+
+    fn attach_tap(
+        &self,
+        connection: &SshConnection,
+        network_interface: &NetworkInterface,
+        tap_name: &str,
+    ) -> Result<()> {
+        let network = self.get_network(&network_interface.network_id)?;
+
+        command_macros::command! {
+            {connection.execute(IP_COMMAND)} link set (tap_name) master (network.bridge_name)
+            if let Some(mtu) = network_interface.mtu {
+                mtu ((mtu))
+            }
+            up
+        }
+        .execute()?;
+
+        // the tap joins the bridge with the default vlan 1, which has to
+        // be swapped for the configured one
+        if let Some(vlan) = network_interface.vlan {
             command_macros::command! {
-                {connection.execute(MKDIR_COMMAND)} --mode 0755 -p (parent_path)
+                {connection.execute(IP_COMMAND)} link set (network.bridge_name) type bridge vlan_filtering 1
+            }
+            .execute()?;
+            command_macros::command! {
+                {connection.execute(BRIDGE_COMMAND)} vlan del dev (tap_name) vid 1
+            }
+            .execute()?;
+            command_macros::command! {
+                {connection.execute(BRIDGE_COMMAND)} vlan add dev (tap_name) vid ((vlan)) pvid untagged
             }
             .execute()?;
         }
 
         Ok(())
     }
+
+    fn remove_stale_taps<T>(&self, connection: &SshConnection, guest_id: T) -> Result<()>
+    where
+        T: AsRef<str>,
+    {
+        let guest_id = guest_id.as_ref();
+
+        let guest = self.get_guest(guest_id)?;
+
+        for (index, network_interface) in guest.network_interfaces.iter().enumerate() {
+            let tap_name = network_interface.tap_name(guest_id, index);
+            if self.link_exists(connection, &tap_name)? {
+                command_macros::command! {
+                    {connection.execute(IP_COMMAND)} link delete (tap_name)
+                }
+                .execute()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn enable_ip_forwarding(&self, connection: &SshConnection) -> Result<()> {
+        command_macros::command! {
+            {connection.execute(SYSCTL_COMMAND)} -q -w net.ipv4.ip_forward=1
+        }
+        .execute()?;
+
+        Ok(())
+    }
+
+    fn load_firewall_ruleset<T>(&self, connection: &SshConnection, ruleset: T) -> Result<()>
+    where
+        T: AsRef<str>,
+    {
+        let command = command_macros::command! {
+            {connection.execute(NFT_COMMAND)} -f -
+        };
+        execute_with_input(command, ruleset)?;
+
+        Ok(())
+    }
+
+    fn ensure_dhcp<T>(&self, connection: &SshConnection, network_id: T) -> Result<()>
+    where
+        T: AsRef<str>,
+    {
+        let network_id = network_id.as_ref();
+
+        let network = self.get_network(network_id)?;
+        let dhcp = match &network.dhcp {
+            Some(dhcp) => dhcp,
+            None => return Ok(()),
+        };
+
+        let config = self.get_dhcp_config(network_id)?;
+
+        let running = self.is_dhcp_running(connection, network_id)?;
+        if running && self.exists(connection, &dhcp.config_path)? {
+            let current_config = self.read_file(connection, &dhcp.config_path)?;
+            if current_config == config {
+                return Ok(());
+            }
+        }
+
+        self.create_parent_dir(connection, &dhcp.config_path)?;
+        self.write_file(connection, &dhcp.config_path, config)?;
+        self.create_parent_dir(connection, &dhcp.pidfile_path)?;
+
+        // dnsmasq does not reload dhcp hosts from the main configuration,
+        // so it has to be restarted to pick up changes
+        if running {
+            self.stop_dhcp(connection, network_id)?;
+        }
+
+        command_macros::command! {
+            {connection.execute(DNSMASQ_COMMAND)} --conf-file=(dhcp.config_path)
+        }
+        .execute()?;
+
+        Ok(())
+    }
+
+    fn exists<T>(&self, connection: &SshConnection, path: T) -> Result<bool>
+    where
+        T: AsRef<Path>,
+    {
+        let path = path.as_ref();
+
+        let status = command_macros::command! {
+            {connection.execute(TEST_COMMAND)} -e (path)
+        }
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+
+        if let Ok(status) = status {
+            Ok(status.success())
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn execute_monitor_command<T, U>(
+        &self,
+        connection: &SshConnection,
+        guest_id: T,
+        monitor_command: U,
+    ) -> Result<String>
+    where
+        T: AsRef<str>,
+        U: AsRef<str>,
+    {
+        let guest = self.get_guest(guest_id)?;
+        let monitor_command = monitor_command.as_ref();
+
+        let mut command = command_macros::command! {
+            {connection.execute(SOCAT_COMMAND)} - UNIX-CONNECT:(guest.monitor_socket_path)
+        };
+        let mut monitor = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|_| ProcessExecutionError::new(&command))?;
+        if let Some(mut stdin) = monitor.stdin.take() {
+            writeln!(stdin, "{monitor_command}").map_err(|_| {
+                MonitorCommandError::new(&guest.monitor_socket_path, monitor_command)
+            })?;
+        }
+
+        let output = monitor
+            .wait_with_output()
+            .map_err(|_| MonitorCommandError::new(&guest.monitor_socket_path, monitor_command))?;
+
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    fn execute_qmp_command<T, U, R>(
+        &self,
+        connection: &SshConnection,
+        guest_id: T,
+        qmp_command: U,
+        arguments: serde_json::Value,
+    ) -> Result<R>
+    where
+        T: AsRef<str>,
+        U: AsRef<str>,
+        R: DeserializeOwned,
+    {
+        let guest_id = guest_id.as_ref();
+        let qmp_command = qmp_command.as_ref();
+
+        let guest = self.get_guest(guest_id)?;
+        let qmp_socket_path = match &guest.qmp_socket_path {
+            Some(qmp_socket_path) => qmp_socket_path,
+            None => anyhow::bail!(MissingQmpSocketError::new(guest_id)),
+        };
+
+        let mut command = command_macros::command! {
+            {connection.execute(SOCAT_COMMAND)} - UNIX-CONNECT:(qmp_socket_path)
+        };
+        let mut qmp = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|_| ProcessExecutionError::new(&command))?;
+        if let Some(mut stdin) = qmp.stdin.take() {
+            // every session has to negotiate capabilities before issuing commands
+            let capabilities = serde_json::json!({ "execute": "qmp_capabilities" });
+            let request = serde_json::json!({ "execute": qmp_command, "arguments": arguments });
+            writeln!(stdin, "{capabilities}\n{request}")
+                .map_err(|_| MonitorCommandError::new(qmp_socket_path, qmp_command))?;
+        }
+
+        let output = qmp
+            .wait_with_output()
+            .map_err(|_| MonitorCommandError::new(qmp_socket_path, qmp_command))?;
+
+        // skip greeting and asynchronous events, the second reply belongs to our command
+        let reply = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+            .filter(|reply| reply.get("return").is_some() || reply.get("error").is_some())
+            .nth(1);
+
+        let error = MonitorCommandError::new(qmp_socket_path, qmp_command);
+        let mut reply = match reply {
+            Some(reply) => reply,
+            None => anyhow::bail!(error),
+        };
+        if let Some(desc) = reply
+            .pointer("/error/desc")
+            .and_then(serde_json::Value::as_str)
+        {
+            anyhow::bail!(error.with_message(desc));
+        }
+
+        reply
+            .get_mut("return")
+            .map(serde_json::Value::take)
+            .and_then(|value| serde_json::from_value(value).ok())
+            .ok_or_else(|| error.into())
+    }
+
+    fn execute_guest_agent_command<T, U, R>(
+        &self,
+        connection: &SshConnection,
+        guest_id: T,
+        agent_command: U,
+        arguments: serde_json::Value,
+    ) -> Result<R>
+    where
+        T: AsRef<str>,
+        U: AsRef<str>,
+        R: DeserializeOwned,
+    {
+        self.execute_guest_agent_command_with_timeout(
+            connection,
+            guest_id,
+            agent_command,
+            arguments,
+            GUEST_AGENT_TIMEOUT,
+        )
+    }
+
+    fn execute_guest_agent_command_with_timeout<T, U, R>(
+        &self,
+        connection: &SshConnection,
+        guest_id: T,
+        agent_command: U,
+        arguments: serde_json::Value,
+        timeout: u64,
+    ) -> Result<R>
+    where
+        T: AsRef<str>,
+        U: AsRef<str>,
+        R: DeserializeOwned,
+    {
+        let guest_id = guest_id.as_ref();
+        let agent_command = agent_command.as_ref();
+
+        let guest = self.get_guest(guest_id)?;
+        let guest_agent_socket_path = match &guest.guest_agent_socket_path {
+            Some(guest_agent_socket_path) => guest_agent_socket_path,
+            None => anyhow::bail!(MissingGuestAgentSocketError::new(guest_id)),
+        };
+
+        // the agent may never answer, e.g. when the guest has not started it
+        // yet, so socat gives up once the socket stays silent for too long
+        let mut command = command_macros::command! {
+            {connection.execute(SOCAT_COMMAND)} -T((timeout)) - UNIX-CONNECT:(guest_agent_socket_path)
+        };
+        let mut agent = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|_| ProcessExecutionError::new(&command))?;
+
+        // the agent keeps replies nobody has read, so every session starts
+        // with a sync to tell our replies apart from leftovers of earlier ones
+        let sync_id = guest_agent_sync_id();
+        let mut stdin = agent.stdin.take();
+        if let Some(stdin) = &mut stdin {
+            let sync =
+                serde_json::json!({ "execute": "guest-sync", "arguments": { "id": sync_id } });
+            let request = serde_json::json!({ "execute": agent_command, "arguments": arguments });
+            writeln!(stdin, "{sync}\n{request}")
+                .map_err(|_| GuestAgentCommandError::new(guest_agent_socket_path, agent_command))?;
+        }
+
+        // unlike qmp the session stays open, so replies are read as they come
+        let sync_reply = serde_json::json!(sync_id);
+        let reply = agent.stdout.take().and_then(|stdout| {
+            BufReader::new(stdout)
+                .lines()
+                .map_while(|line| line.ok())
+                .filter_map(|line| serde_json::from_str::<serde_json::Value>(&line).ok())
+                .skip_while(|reply| reply.get("return") != Some(&sync_reply))
+                .skip(1)
+                .find(|reply| reply.get("return").is_some() || reply.get("error").is_some())
+        });
+
+        drop(stdin);
+        agent
+            .wait()
+            .map_err(|_| GuestAgentCommandError::new(guest_agent_socket_path, agent_command))?;
+
+        reply
+            .and_then(|mut reply| reply.get_mut("return").map(serde_json::Value::take))
+            .and_then(|value| serde_json::from_value(value).ok())
+            .ok_or_else(|| {
+                GuestAgentCommandError::new(guest_agent_socket_path, agent_command).into()
+            })
+    }
+
+    fn send_guest_agent_command<T, U>(
+        &self,
+        connection: &SshConnection,
+        guest_id: T,
+        agent_command: U,
+        arguments: serde_json::Value,
+    ) -> Result<()>
+    where
+        T: AsRef<str>,
+        U: AsRef<str>,
+    {
+        let guest_id = guest_id.as_ref();
+        let agent_command = agent_command.as_ref();
+
+        let guest = self.get_guest(guest_id)?;
+        let guest_agent_socket_path = match &guest.guest_agent_socket_path {
+            Some(guest_agent_socket_path) => guest_agent_socket_path,
+            None => anyhow::bail!(MissingGuestAgentSocketError::new(guest_id)),
+        };
+
+        // commands like guest-shutdown never reply, so nothing is waited for
+        let request = serde_json::json!({ "execute": agent_command, "arguments": arguments });
+        let command = command_macros::command! {
+            {connection.execute(SOCAT_COMMAND)} -T((GUEST_AGENT_TIMEOUT)) - UNIX-CONNECT:(guest_agent_socket_path)
+        };
+        execute_with_input(command, format!("{request}\n"))
+            .map_err(|_| GuestAgentCommandError::new(guest_agent_socket_path, agent_command))?;
+
+        Ok(())
+    }
+
+    fn is_guest_agent_running<T>(&self, connection: &SshConnection, guest_id: T) -> Result<bool>
+    where
+        T: AsRef<str>,
+    {
+        let guest_id = guest_id.as_ref();
+
+        let guest = self.get_guest(guest_id)?;
+        if guest.guest_agent_socket_path.is_none() {
+            return Ok(false);
+        }
+
+        // a running agent answers right away, so there is no point in
+        // waiting as long as for real commands
+        let reply = self.execute_guest_agent_command_with_timeout::<_, _, IgnoredAny>(
+            connection,
+            guest_id,
+            "guest-ping",
+            serde_json::json!({}),
+            GUEST_AGENT_PING_TIMEOUT,
+        );
+
+        Ok(reply.is_ok())
+    }
+
+    fn is_dhcp_running<T>(&self, connection: &SshConnection, network_id: T) -> Result<bool>
+    where
+        T: AsRef<str>,
+    {
+        let network = self.get_network(network_id)?;
+        let dhcp = match &network.dhcp {
+            Some(dhcp) => dhcp,
+            None => return Ok(false),
+        };
+
+        let mut command = command_macros::command! {
+            {connection.execute(PGREP_COMMMAND)} --full --pidfile (dhcp.pidfile_path) (DNSMASQ_COMMAND)
+        };
+        let status = command
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map_err(|_| ProcessExecutionError::new(&command))?;
+
+        Ok(status.success())
+    }
+
+    fn is_tpm_running<T>(&self, connection: &SshConnection, guest_id: T) -> Result<bool>
+    where
+        T: AsRef<str>,
+    {
+        let tpm_state_path = match self.get_guest_tpm_state_path(guest_id)? {
+            Some(tpm_state_path) => tpm_state_path,
+            None => return Ok(false),
+        };
+
+        let mut command = command_macros::command! {
+            {connection.execute(PGREP_COMMMAND)} --full --pidfile (tpm::pidfile_path(&tpm_state_path)) (SWTPM_COMMAND)
+        };
+        let status = command
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map_err(|_| ProcessExecutionError::new(&command))?;
+
+        Ok(status.success())
+    }
+
+    fn is_incremental_backup<T, U, V>(
+        &self,
+        connection: &SshConnection,
+        guest_id: T,
+        path: U,
+        backup_id: V,
+    ) -> Result<bool>
+    where
+        T: AsRef<str>,
+        U: AsRef<Path>,
+        V: AsRef<str>,
+    {
+        let guest_id = guest_id.as_ref();
+
+        // all disks are backed up in the same mode, so checking the first one is enough
+        let disk = match self.get_guest_disks(guest_id)?.first() {
+            Some(disk) => disk,
+            None => return Ok(false),
+        };
+
+        #[derive(Deserialize)]
+        struct QemuImgInfo {
+            #[serde(rename = "backing-filename")]
+            backing_filename: Option<String>,
+        }
+
+        let backup_path = backup::backup_path(path, guest_id, backup_id);
+        let info = command_macros::command! {
+            {connection.execute(QEMU_IMG_COMMAND)} info --output=json (backup::disk_path(backup_path, &disk.label))
+        }
+        .execute_and_parse_json_output::<QemuImgInfo>()?;
+
+        Ok(info.backing_filename.is_some())
+    }
+
+    fn is_booted<T>(&self, connection: &SshConnection, guest_id: T) -> Result<bool>
+    where
+        T: AsRef<str>,
+    {
+        let guest = self.get_guest(guest_id)?;
+
+        let mut command = command_macros::command! {
+            {connection.execute(PGREP_COMMMAND)} --full --pidfile (guest.pidfile_path) qemu
+        };
+        let status = command
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map_err(|_| ProcessExecutionError::new(&command))?;
+
+        Ok(status.success())
+    }
+
+    fn link_exists<T>(&self, connection: &SshConnection, name: T) -> Result<bool>
+    where
+        T: AsRef<str>,
+    {
+        let name = name.as_ref();
+
+        let mut command = command_macros::command! {
+            {connection.execute(IP_COMMAND)} link show dev (name)
+        };
+        let status = command
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map_err(|_| ProcessExecutionError::new(&command))?;
+
+        Ok(status.success())
+    }
+
+    fn read_file<T>(&self, connection: &SshConnection, path: T) -> Result<String>
+    where
+        T: AsRef<Path>,
+    {
+        let path = path.as_ref();
+
+        command_macros::command! {
+            {connection.execute(CAT_COMMAND)} (path)
+        }
+        .execute()
+    }
+
+    fn stop_dhcp<T>(&self, connection: &SshConnection, network_id: T) -> Result<()>
+    where
+        T: AsRef<str>,
+    {
+        let network = self.get_network(network_id)?;
+        let dhcp = match &network.dhcp {
+            Some(dhcp) => dhcp,
+            None => return Ok(()),
+        };
+
+        command_macros::command! {
+            {connection.execute(PKILL_COMMAND)} --full --pidfile (dhcp.pidfile_path) (DNSMASQ_COMMAND)
+        }
+        .execute()?;
+
+        // give dnsmasq a moment to release its sockets
+        let delay = Duration::from_millis(1000);
+        std::thread::sleep(delay);
+
+        Ok(())
+    }
+
+    fn start_tpm<T>(&self, connection: &SshConnection, guest_id: T) -> Result<()>
+    where
+        T: AsRef<str>,
+    {
+        let guest_id = guest_id.as_ref();
+
+        let tpm_state_path = match self.get_guest_tpm_state_path(guest_id)? {
+            Some(tpm_state_path) => tpm_state_path,
+            None => return Ok(()),
+        };
+
+        if self.is_tpm_running(connection, guest_id)? {
+            return Ok(());
+        }
+
+        // swtpm drops privileges like qemu does, so the state has to stay
+        // writable for the unprivileged user
+        command_macros::command! {
+            {connection.execute(MKDIR_COMMAND)} --mode 0700 -p (tpm_state_path)
+        }
+        .execute()?;
+        command_macros::command! {
+            {connection.execute(CHOWN_COMMAND)} (QEMU_USER) (tpm_state_path)
+        }
+        .execute()?;
+
+        // swtpm exits on its own once qemu closes the connection
+        command_macros::command! {
+            {connection.execute(SWTPM_COMMAND)} socket
+            --tpm2
+            --tpmstate dir=(tpm_state_path)
+            --ctrl type=unixio,path=(tpm::socket_path(&tpm_state_path))
+            --pid file=(tpm::pidfile_path(&tpm_state_path))
+            --runas (QEMU_USER)
+            --terminate
+            --daemon
+        }
+        .execute()?;
+
+        Ok(())
+    }
+
+    fn stop_tpm<T>(&self, connection: &SshConnection, guest_id: T) -> Result<()>
+    where
+        T: AsRef<str>,
+    {
+        let guest_id = guest_id.as_ref();
+
+        let tpm_state_path = match self.get_guest_tpm_state_path(guest_id)? {
+            Some(tpm_state_path) => tpm_state_path,
+            None => return Ok(()),
+        };
+
+        if !self.is_tpm_running(connection, guest_id)? {
+            return Ok(());
+        }
+
+        command_macros::command! {
+            {connection.execute(PKILL_COMMAND)} --full --pidfile (tpm::pidfile_path(&tpm_state_path)) (SWTPM_COMMAND)
+        }
+        .execute()?;
+
+        Ok(())
+    }
+
+    fn write_file<T, U>(&self, connection: &SshConnection, path: T, contents: U) -> Result<()>
+    where
+        T: AsRef<Path>,
+        U: AsRef<str>,
+    {
+        let path = path.as_ref();
+        let contents = contents.as_ref();
+
+        let command = command_macros::command! {
+            {connection.execute(TEE_COMMAND)} (path)
+        };
+        execute_with_input(command, contents)?;
+
+        Ok(())
+    }
+
+    fn create_parent_dir<T>(&self, connection: &SshConnection, path: T) -> Result<()>
+    where
+        T: AsRef<Path>,
+    {
+        let path = path.as_ref();
+
+        if let Some(parent_path) = path.parent() {
+            command_macros::command! {
+                {connection.execute(MKDIR_COMMAND)} --mode 0755 -p (parent_path)
+            }
+            .execute()?;
+        }
+
+        Ok(())
+    }
+}
+
+fn execute_with_input<T>(mut command: Command, input: T) -> Result<()>
+where
+    T: AsRef<str>,
+{
+    let input = input.as_ref();
+
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|_| ProcessExecutionError::new(&command))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(input.as_bytes())
+            .map_err(|_| ProcessExecutionError::new(&command))?;
+    }
+
+    let output = child
+        .wait_with_output()
+        .map_err(|_| ProcessExecutionError::new(&command))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!(CommandExecutionFailedError::new(&command, "", stderr));
+    }
+
+    Ok(())
+}
+
+fn format_size(size: u64) -> String {
+    const UNITS: [&str; 5] = ["K", "M", "G", "T", "P"];
+
+    if size < 1024 {
+        return size.to_string();
+    }
+
+    let mut size = size as f64 / 1024.0;
+    let mut unit = UNITS[0];
+    for next_unit in &UNITS[1..] {
+        if size < 1024.0 {
+            break;
+        }
+        size /= 1024.0;
+        unit = next_unit;
+    }
+
+    let size = format!("{size:.1}");
+    let size = size.strip_suffix(".0").unwrap_or(&size);
+
+    format!("{size}{unit}")
+}
+
+fn guest_agent_sync_id() -> u32 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|duration| duration.subsec_nanos())
+        .unwrap_or_default()
+}
+
+// per-guest files default to living next to the first disk, so removing
+// the guest directory takes everything with it
+fn guest_path<T>(guest: &Guest, path: &Option<PathBuf>, file_name: T) -> Option<PathBuf>
+where
+    T: AsRef<str>,
+{
+    if let Some(path) = path {
+        return Some(path.clone());
+    }
+
+    guest
+        .disks
+        .first()
+        .and_then(|disk| disk.path.parent())
+        .map(|parent_path| parent_path.join(file_name.as_ref()))
+}
+
+fn guest_nvram_path<T>(guest_id: T, guest: &Guest) -> Result<Option<PathBuf>>
+where
+    T: AsRef<str>,
+{
+    let guest_id = guest_id.as_ref();
+
+    if guest.firmware != Firmware::Uefi {
+        return Ok(None);
+    }
+
+    // qcow2 keeps the variables in internal snapshots along with the disks
+    match guest_path(guest, &guest.nvram_path, format!("{guest_id}.nvram.qcow2")) {
+        Some(nvram_path) => Ok(Some(nvram_path)),
+        None => anyhow::bail!(MissingNvramPathError::new(guest_id)),
+    }
+}
+
+fn path_with_suffix<T, U>(path: T, suffix: U) -> PathBuf
+where
+    T: AsRef<Path>,
+    U: AsRef<str>,
+{
+    let mut path = path.as_ref().as_os_str().to_owned();
+    path.push(suffix.as_ref());
+
+    PathBuf::from(path)
+}
+
+fn parse_ipv4_network(address: &str) -> Option<(Ipv4Addr, u32)> {
+    let (address, prefix_length) = address.split_once('/')?;
+    let address = address.parse().ok()?;
+    let prefix_length = prefix_length.parse().ok().filter(|length| *length <= 32)?;
+
+    Some((address, prefix_length))
 }
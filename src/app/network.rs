@@ -1,6 +1,14 @@
+use super::dhcp::Dhcp;
+use super::network_mode::NetworkMode;
 use serde::Deserialize;
 
 #[derive(Deserialize)]
 pub struct Network {
     pub bridge_name: String,
+    #[serde(default)]
+    pub address: Option<String>,
+    #[serde(default)]
+    pub dhcp: Option<Dhcp>,
+    #[serde(default)]
+    pub mode: NetworkMode,
 }
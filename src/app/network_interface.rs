@@ -1,15 +1,110 @@
 use serde::Deserialize;
+use serde::Serialize;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
 pub struct NetworkInterface {
     #[serde(rename = "network")]
     pub network_id: String,
-    pub mac_address: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mac_address: Option<String>,
     #[serde(default = "default_network_interface_model")]
     pub model: String,
-    pub tap_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tap_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queues: Option<u32>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub vhost: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mtu: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rombar: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bootindex: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vlan: Option<u16>,
+}
+
+impl NetworkInterface {
+    pub fn mac_address<T>(&self, guest_id: T, index: usize) -> String
+    where
+        T: AsRef<str>,
+    {
+        match &self.mac_address {
+            Some(mac_address) => mac_address.clone(),
+            None => default_mac_address(guest_id, index),
+        }
+    }
+
+    pub fn tap_name<T>(&self, guest_id: T, index: usize) -> String
+    where
+        T: AsRef<str>,
+    {
+        match &self.tap_name {
+            Some(tap_name) => tap_name.clone(),
+            None => default_tap_name(guest_id, index),
+        }
+    }
 }
 
 fn default_network_interface_model() -> String {
     "virtio-net-pci-non-transitional".to_string()
 }
+
+pub const TAP_NAME_MAX_LENGTH: usize = 15;
+
+fn default_mac_address<T>(guest_id: T, index: usize) -> String
+where
+    T: AsRef<str>,
+{
+    // derived from the guest and the position of the interface, so the
+    // address survives restarts and reordering of other guests
+    let hash = stable_hash(format!("{guest_id}/{index}", guest_id = guest_id.as_ref()));
+
+    format!(
+        "52:54:00:{:02x}:{:02x}:{:02x}",
+        (hash >> 24) as u8,
+        (hash >> 16) as u8,
+        (hash >> 8) as u8,
+    )
+}
+
+fn default_tap_name<T>(guest_id: T, index: usize) -> String
+where
+    T: AsRef<str>,
+{
+    let guest_id = guest_id.as_ref();
+
+    let tap_name = format!("mima-{guest_id}-{index}");
+    if is_valid_tap_name(&tap_name) {
+        return tap_name;
+    }
+
+    format!("mima-{:06x}-{index}", stable_hash(guest_id) >> 8)
+}
+
+pub fn is_valid_tap_name<T>(tap_name: T) -> bool
+where
+    T: AsRef<str>,
+{
+    tap_name.as_ref().len() <= TAP_NAME_MAX_LENGTH
+}
+
+// std hashers are not guaranteed to be stable between releases, while
+// generated names must never change, so this is fnv-1a followed by the
+// murmur3 finalizer to spread names differing only in the last character
+fn stable_hash<T>(value: T) -> u32
+where
+    T: AsRef<str>,
+{
+    let mut hash = value.as_ref().bytes().fold(0x811c9dc5_u32, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x01000193)
+    });
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x85ebca6b);
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(0xc2b2ae35);
+    hash ^= hash >> 16;
+
+    hash
+}
@@ -0,0 +1,20 @@
+use serde::Deserialize;
+
+#[derive(Clone, Copy, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum NetworkMode {
+    Isolated,
+    Nat,
+    #[default]
+    Bridged,
+}
+
+impl NetworkMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Isolated => "isolated",
+            Self::Nat => "nat",
+            Self::Bridged => "bridged",
+        }
+    }
+}
@@ -0,0 +1,11 @@
+use super::protocol::Protocol;
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Deserialize, Serialize)]
+pub struct PortForward {
+    pub host_port: u16,
+    pub guest_port: u16,
+    #[serde(default)]
+    pub protocol: Protocol,
+}
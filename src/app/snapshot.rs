@@ -3,4 +3,5 @@ use std::time::Duration;
 pub struct Snapshot {
     pub id: String,
     pub timestamp: Duration,
+    pub vm_state_size: u64,
 }
@@ -1,32 +1,47 @@
+use super::host_key_checking::HostKeyChecking;
+use super::ssh_settings::SshSettings;
 use std::process::Command;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
 
 const ROOT_USER: &str = "root";
 const CONNECTION_TIMEOUT: u64 = 10;
+const SUDO_COMMAND: &str = "sudo";
+const CONTROL_PATH: &str = "~/.ssh/mima-%C";
+// masters are stopped on exit, this only reaps the ones left behind
+const CONTROL_PERSIST: u64 = 60;
 
 pub struct SshConnection {
     destination: String,
+    settings: SshSettings,
+    privileged: bool,
+    multiplexed: bool,
+    used: AtomicBool,
 }
 
 impl SshConnection {
-    pub fn new<T>(destination: T) -> Self
+    pub fn new<T>(destination: T, settings: SshSettings, privileged: bool) -> Self
     where
         T: AsRef<str>,
     {
         Self {
             destination: destination.as_ref().to_owned(),
+            settings,
+            privileged,
+            multiplexed: false,
+            used: AtomicBool::new(false),
         }
     }
 
+    pub fn multiplexed(mut self) -> Self {
+        // commands share a single master connection, which is stopped once
+        // the connection goes away, instead of authenticating every time
+        self.multiplexed = true;
+        self
+    }
+
     pub fn command(&self) -> Command {
-        command_macros::command! {
-            ssh
-            -o BatchMode=yes
-            -o ConnectTimeout=((CONNECTION_TIMEOUT))
-            -o ForwardAgent=yes
-            -o StrictHostKeyChecking=no
-            -o UserKnownHostsFile=/dev/null
-            (ROOT_USER)@(self.destination)
-        }
+        self.build_command(false)
     }
 
     pub fn execute<T>(&self, command: T) -> Command
@@ -37,7 +52,138 @@ impl SshConnection {
 
         command_macros::command! {
             {self.command()}
+            if self.needs_sudo() {
+                (SUDO_COMMAND) -n
+            }
             (command)
         }
     }
+
+    pub fn execute_in_terminal<T>(&self, command: T) -> Command
+    where
+        T: AsRef<str>,
+    {
+        let command = command.as_ref();
+
+        // a pseudo-terminal makes keys like ctrl-c reach the program instead
+        // of killing the connection
+        command_macros::command! {
+            {self.build_command(true)}
+            if self.needs_sudo() {
+                (SUDO_COMMAND) -n
+            }
+            (command)
+        }
+    }
+
+    pub fn copy(&self) -> Command {
+        self.used.store(true, Ordering::Relaxed);
+
+        // scp takes the same options as ssh, so copies connect the way
+        // commands do
+        command_macros::command! {
+            scp
+            [self.options()]
+        }
+    }
+
+    pub fn remote_path<T>(&self, path: T) -> String
+    where
+        T: AsRef<str>,
+    {
+        let path = path.as_ref();
+
+        format!(
+            "{user}@{destination}:{path}",
+            destination = self.destination,
+            user = self.user(),
+        )
+    }
+
+    fn build_command(&self, terminal: bool) -> Command {
+        self.used.store(true, Ordering::Relaxed);
+
+        command_macros::command! {
+            ssh
+            if terminal {
+                -t
+            }
+            [self.options()]
+            (self.user())@(self.destination)
+        }
+    }
+
+    fn options(&self) -> Vec<String> {
+        let settings = &self.settings;
+
+        let connect_timeout = settings.connect_timeout.unwrap_or(CONNECTION_TIMEOUT);
+        let host_key_checking = settings.host_key_checking.unwrap_or_default();
+
+        let mut options = vec![
+            "BatchMode=yes".to_string(),
+            format!("ConnectTimeout={connect_timeout}"),
+            "ForwardAgent=yes".to_string(),
+        ];
+        if let Some(port) = settings.port {
+            options.push(format!("Port={port}"));
+        }
+        if let Some(identity_file) = &settings.identity_file {
+            options.push(format!("IdentityFile={}", identity_file.display()));
+        }
+        if let Some(jump_host) = &settings.jump_host {
+            options.push(format!("ProxyJump={jump_host}"));
+        }
+        options.push(format!(
+            "StrictHostKeyChecking={}",
+            host_key_checking.as_str()
+        ));
+        // keys of hosts that are not checked are not worth remembering
+        match &settings.known_hosts_file {
+            Some(known_hosts_file) => {
+                options.push(format!("UserKnownHostsFile={}", known_hosts_file.display()));
+            }
+            None if host_key_checking == HostKeyChecking::No => {
+                options.push("UserKnownHostsFile=/dev/null".to_string());
+            }
+            None => {}
+        }
+        if self.multiplexed {
+            options.push("ControlMaster=auto".to_string());
+            options.push(format!("ControlPath={CONTROL_PATH}"));
+            options.push(format!("ControlPersist={CONTROL_PERSIST}"));
+        }
+
+        options
+            .into_iter()
+            .flat_map(|option| ["-o".to_string(), option])
+            .collect()
+    }
+
+    fn user(&self) -> &str {
+        self.settings.user.as_deref().unwrap_or(ROOT_USER)
+    }
+
+    fn needs_sudo(&self) -> bool {
+        // commands of privileged connections run as root, through sudo when
+        // logging in as somebody else
+        self.privileged && self.user() != ROOT_USER
+    }
+}
+
+impl Drop for SshConnection {
+    fn drop(&mut self) {
+        if !self.multiplexed || !self.used.load(Ordering::Relaxed) {
+            return;
+        }
+
+        // stopping lets sessions still using the master finish, and a
+        // failure merely leaves it to expire on its own
+        let _ = command_macros::command! {
+            ssh
+            [self.options()]
+            -O stop
+            (self.user())@(self.destination)
+        }
+        .output();
+    }
 }
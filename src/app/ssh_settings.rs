@@ -0,0 +1,46 @@
+use super::host_key_checking::HostKeyChecking;
+use serde::Deserialize;
+use serde::Serialize;
+use std::path::PathBuf;
+
+#[derive(Clone, Default, Deserialize, Serialize)]
+pub struct SshSettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identity_file: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jump_host: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connect_timeout: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host_key_checking: Option<HostKeyChecking>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub known_hosts_file: Option<PathBuf>,
+}
+
+impl SshSettings {
+    pub fn or(&self, defaults: &Self) -> Self {
+        // settings that are not set here are taken from the defaults
+        Self {
+            user: self.user.clone().or_else(|| defaults.user.clone()),
+            port: self.port.or(defaults.port),
+            identity_file: self
+                .identity_file
+                .clone()
+                .or_else(|| defaults.identity_file.clone()),
+            jump_host: self
+                .jump_host
+                .clone()
+                .or_else(|| defaults.jump_host.clone()),
+            connect_timeout: self.connect_timeout.or(defaults.connect_timeout),
+            host_key_checking: self.host_key_checking.or(defaults.host_key_checking),
+            known_hosts_file: self
+                .known_hosts_file
+                .clone()
+                .or_else(|| defaults.known_hosts_file.clone()),
+        }
+    }
+}
@@ -0,0 +1,16 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+pub fn socket_path<T>(state_path: T) -> PathBuf
+where
+    T: AsRef<Path>,
+{
+    state_path.as_ref().join("swtpm.socket")
+}
+
+pub fn pidfile_path<T>(state_path: T) -> PathBuf
+where
+    T: AsRef<Path>,
+{
+    state_path.as_ref().join("swtpm.pid")
+}
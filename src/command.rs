@@ -8,11 +8,20 @@ use std::process::Command;
 
 pub trait Execute {
     fn execute(&mut self) -> Result<String>;
+    fn execute_with_exit_codes(&mut self, exit_codes: &[i32]) -> Result<String>;
     fn execute_and_parse_json_output<T: DeserializeOwned>(&mut self) -> Result<T>;
+    fn execute_with_exit_codes_and_parse_json_output<T: DeserializeOwned>(
+        &mut self,
+        exit_codes: &[i32],
+    ) -> Result<T>;
 }
 
 impl Execute for Command {
     fn execute(&mut self) -> Result<String> {
+        self.execute_with_exit_codes(&[0])
+    }
+
+    fn execute_with_exit_codes(&mut self, exit_codes: &[i32]) -> Result<String> {
         let output = self
             .output()
             .map_err(|_| ProcessExecutionError::new(self))?;
@@ -22,15 +31,21 @@ impl Execute for Command {
         let stderr =
             String::from_utf8(output.stderr).map_err(|_| ParseStreamError::new("stderr"))?;
 
-        if !output.status.success() {
-            anyhow::bail!(CommandExecutionFailedError::new(self, stdout, stderr));
+        match output.status.code() {
+            Some(code) if exit_codes.contains(&code) => Ok(stdout),
+            _ => anyhow::bail!(CommandExecutionFailedError::new(self, stdout, stderr)),
         }
-
-        Ok(stdout)
     }
 
     fn execute_and_parse_json_output<T: DeserializeOwned>(&mut self) -> Result<T> {
-        let stdout = self.execute()?;
+        self.execute_with_exit_codes_and_parse_json_output(&[0])
+    }
+
+    fn execute_with_exit_codes_and_parse_json_output<T: DeserializeOwned>(
+        &mut self,
+        exit_codes: &[i32],
+    ) -> Result<T> {
+        let stdout = self.execute_with_exit_codes(exit_codes)?;
         let value = serde_json::from_str(&stdout)
             .map_err(|_| ParseCommandOutputError::new(self, stdout))?;
 
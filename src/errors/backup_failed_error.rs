@@ -0,0 +1,46 @@
+use colored::*;
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result;
+
+#[derive(Debug)]
+pub struct BackupFailedError {
+    disk_id: String,
+    guest_id: String,
+    message: String,
+}
+
+impl BackupFailedError {
+    pub fn new<T, U, V>(guest_id: T, disk_id: U, message: V) -> Self
+    where
+        T: AsRef<str>,
+        U: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let disk_id = disk_id.as_ref().to_string();
+        let guest_id = guest_id.as_ref().to_string();
+        let message = message.as_ref().to_string();
+
+        Self {
+            disk_id,
+            guest_id,
+            message,
+        }
+    }
+}
+
+impl Display for BackupFailedError {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let disk_id = self.disk_id.yellow();
+        let guest_id = self.guest_id.yellow();
+        let message = &self.message;
+
+        write!(
+            f,
+            "backup of disk '{disk_id}' for guest '{guest_id}' failed: {message}"
+        )
+    }
+}
+
+impl Error for BackupFailedError {}
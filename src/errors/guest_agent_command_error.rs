@@ -0,0 +1,42 @@
+use colored::*;
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result;
+use std::path::Path;
+
+#[derive(Debug)]
+pub struct GuestAgentCommandError {
+    command: String,
+    guest_agent_socket_path: String,
+}
+
+impl GuestAgentCommandError {
+    pub fn new<T, U>(guest_agent_socket_path: T, command: U) -> Self
+    where
+        T: AsRef<Path>,
+        U: AsRef<str>,
+    {
+        let command = command.as_ref().to_string();
+        let guest_agent_socket_path = guest_agent_socket_path.as_ref().display().to_string();
+
+        Self {
+            command,
+            guest_agent_socket_path,
+        }
+    }
+}
+
+impl Display for GuestAgentCommandError {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let command = self.command.yellow();
+        let guest_agent_socket_path = self.guest_agent_socket_path.yellow();
+
+        write!(
+            f,
+            "failed to issue command '{command}' to guest agent socket '{guest_agent_socket_path}'"
+        )
+    }
+}
+
+impl Error for GuestAgentCommandError {}
@@ -0,0 +1,39 @@
+use colored::*;
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result;
+
+#[derive(Debug)]
+pub struct GuestCommandFailedError {
+    exit_code: i32,
+    guest_id: String,
+}
+
+impl GuestCommandFailedError {
+    pub fn new<T>(guest_id: T, exit_code: i32) -> Self
+    where
+        T: AsRef<str>,
+    {
+        let guest_id = guest_id.as_ref().to_string();
+
+        Self {
+            exit_code,
+            guest_id,
+        }
+    }
+}
+
+impl Display for GuestCommandFailedError {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let exit_code = self.exit_code.to_string().yellow();
+        let guest_id = self.guest_id.yellow();
+
+        write!(
+            f,
+            "command in guest '{guest_id}' exited with code {exit_code}"
+        )
+    }
+}
+
+impl Error for GuestCommandFailedError {}
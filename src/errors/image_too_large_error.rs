@@ -0,0 +1,47 @@
+use colored::*;
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result;
+use std::path::Path;
+
+#[derive(Debug)]
+pub struct ImageTooLargeError {
+    disk_id: String,
+    guest_id: String,
+    image_path: String,
+}
+
+impl ImageTooLargeError {
+    pub fn new<T, U, V>(guest_id: T, disk_id: U, image_path: V) -> Self
+    where
+        T: AsRef<str>,
+        U: AsRef<str>,
+        V: AsRef<Path>,
+    {
+        let disk_id = disk_id.as_ref().to_string();
+        let guest_id = guest_id.as_ref().to_string();
+        let image_path = image_path.as_ref().display().to_string();
+
+        Self {
+            disk_id,
+            guest_id,
+            image_path,
+        }
+    }
+}
+
+impl Display for ImageTooLargeError {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let disk_id = self.disk_id.yellow();
+        let guest_id = self.guest_id.yellow();
+        let image_path = self.image_path.yellow();
+
+        write!(
+            f,
+            "image '{image_path}' does not fit into disk '{disk_id}' of guest '{guest_id}'"
+        )
+    }
+}
+
+impl Error for ImageTooLargeError {}
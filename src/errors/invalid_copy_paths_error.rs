@@ -0,0 +1,38 @@
+use colored::*;
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result;
+
+#[derive(Debug)]
+pub struct InvalidCopyPathsError {
+    source: String,
+    target: String,
+}
+
+impl InvalidCopyPathsError {
+    pub fn new<T, U>(source: T, target: U) -> Self
+    where
+        T: AsRef<str>,
+        U: AsRef<str>,
+    {
+        let source = source.as_ref().to_string();
+        let target = target.as_ref().to_string();
+
+        Self { source, target }
+    }
+}
+
+impl Display for InvalidCopyPathsError {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let source = self.source.yellow();
+        let target = self.target.yellow();
+
+        write!(
+            f,
+            "exactly one of '{source}' and '{target}' has to be a guest path like 'GUEST:PATH'"
+        )
+    }
+}
+
+impl Error for InvalidCopyPathsError {}
@@ -0,0 +1,34 @@
+use colored::*;
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result;
+
+#[derive(Debug)]
+pub struct InvalidNetworkAddressError {
+    network_id: String,
+}
+
+impl InvalidNetworkAddressError {
+    pub fn new<T>(network_id: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        let network_id = network_id.as_ref().to_string();
+
+        Self { network_id }
+    }
+}
+
+impl Display for InvalidNetworkAddressError {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let network_id = self.network_id.yellow();
+
+        write!(
+            f,
+            "network '{network_id}' needs an IPv4 address with prefix length"
+        )
+    }
+}
+
+impl Error for InvalidNetworkAddressError {}
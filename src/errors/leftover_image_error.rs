@@ -0,0 +1,35 @@
+use colored::*;
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result;
+use std::path::Path;
+
+#[derive(Debug)]
+pub struct LeftoverImageError {
+    path: String,
+}
+
+impl LeftoverImageError {
+    pub fn new<T>(path: T) -> Self
+    where
+        T: AsRef<Path>,
+    {
+        let path = path.as_ref().display().to_string();
+
+        Self { path }
+    }
+}
+
+impl Display for LeftoverImageError {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let path = self.path.yellow();
+
+        write!(
+            f,
+            "image '{path}' is left over from a previous compaction, remove it first"
+        )
+    }
+}
+
+impl Error for LeftoverImageError {}
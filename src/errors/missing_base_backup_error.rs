@@ -0,0 +1,34 @@
+use colored::*;
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result;
+
+#[derive(Debug)]
+pub struct MissingBaseBackupError {
+    guest_id: String,
+}
+
+impl MissingBaseBackupError {
+    pub fn new<T>(guest_id: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        let guest_id = guest_id.as_ref().to_string();
+
+        Self { guest_id }
+    }
+}
+
+impl Display for MissingBaseBackupError {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let guest_id = self.guest_id.yellow();
+
+        write!(
+            f,
+            "no backup of guest '{guest_id}' to base incremental backup on"
+        )
+    }
+}
+
+impl Error for MissingBaseBackupError {}
@@ -0,0 +1,38 @@
+use colored::*;
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result;
+
+#[derive(Debug)]
+pub struct MissingGuestPathError {
+    guest_id: String,
+    setting: String,
+}
+
+impl MissingGuestPathError {
+    pub fn new<T, U>(guest_id: T, setting: U) -> Self
+    where
+        T: AsRef<str>,
+        U: AsRef<str>,
+    {
+        let guest_id = guest_id.as_ref().to_string();
+        let setting = setting.as_ref().to_string();
+
+        Self { guest_id, setting }
+    }
+}
+
+impl Display for MissingGuestPathError {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let guest_id = self.guest_id.yellow();
+        let setting = self.setting.yellow();
+
+        write!(
+            f,
+            "guest '{guest_id}' has no disks to derive '{setting}' from, it has to be set explicitly"
+        )
+    }
+}
+
+impl Error for MissingGuestPathError {}
@@ -0,0 +1,31 @@
+use colored::*;
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result;
+
+#[derive(Debug)]
+pub struct MissingQmpSocketError {
+    guest_id: String,
+}
+
+impl MissingQmpSocketError {
+    pub fn new<T>(guest_id: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        let guest_id = guest_id.as_ref().to_string();
+
+        Self { guest_id }
+    }
+}
+
+impl Display for MissingQmpSocketError {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let guest_id = self.guest_id.yellow();
+
+        write!(f, "guest '{guest_id}' has no QMP socket configured")
+    }
+}
+
+impl Error for MissingQmpSocketError {}
@@ -0,0 +1,38 @@
+use colored::*;
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result;
+
+#[derive(Debug)]
+pub struct MissingTapError {
+    guest_id: String,
+    tap_name: String,
+}
+
+impl MissingTapError {
+    pub fn new<T, U>(guest_id: T, tap_name: U) -> Self
+    where
+        T: AsRef<str>,
+        U: AsRef<str>,
+    {
+        let guest_id = guest_id.as_ref().to_string();
+        let tap_name = tap_name.as_ref().to_string();
+
+        Self { guest_id, tap_name }
+    }
+}
+
+impl Display for MissingTapError {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let guest_id = self.guest_id.yellow();
+        let tap_name = self.tap_name.yellow();
+
+        write!(
+            f,
+            "tap '{tap_name}' of running guest '{guest_id}' is missing, the guest has to be restarted"
+        )
+    }
+}
+
+impl Error for MissingTapError {}
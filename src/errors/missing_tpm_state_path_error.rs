@@ -0,0 +1,34 @@
+use colored::*;
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result;
+
+#[derive(Debug)]
+pub struct MissingTpmStatePathError {
+    guest_id: String,
+}
+
+impl MissingTpmStatePathError {
+    pub fn new<T>(guest_id: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        let guest_id = guest_id.as_ref().to_string();
+
+        Self { guest_id }
+    }
+}
+
+impl Display for MissingTpmStatePathError {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let guest_id = self.guest_id.yellow();
+
+        write!(
+            f,
+            "guest '{guest_id}' has no disks to keep TPM state next to, set tpm_state_path instead"
+        )
+    }
+}
+
+impl Error for MissingTpmStatePathError {}
@@ -1,25 +1,85 @@
+mod backup_failed_error;
+mod booted_guest_error;
 mod command_execution_failed_error;
+mod duplicate_guest_error;
 mod duplicate_snapshot_error;
+mod guest_agent_command_error;
+mod guest_command_failed_error;
+mod image_too_large_error;
+mod invalid_copy_paths_error;
+mod invalid_network_address_error;
+mod invalid_tap_name_error;
+mod leftover_image_error;
+mod missing_base_backup_error;
 mod missing_configuration_error;
+mod missing_guest_agent_socket_error;
+mod missing_guest_path_error;
+mod missing_nvram_path_error;
+mod missing_qmp_socket_error;
+mod missing_serial_log_error;
+mod missing_serial_socket_error;
+mod missing_tap_error;
+mod missing_tpm_state_path_error;
 mod monitor_command_error;
+mod network_in_use_error;
+mod network_interface_conflict_error;
+mod parse_bundle_error;
 mod parse_command_output_error;
 mod parse_configuration_error;
 mod parse_stream_error;
 mod process_execution_error;
 mod read_configuration_error;
+mod read_user_data_error;
+mod snapshot_loss_error;
+mod unknown_backup_error;
+mod unknown_disk_error;
 mod unknown_guest_error;
 mod unknown_network_error;
 mod unknown_snapshot_error;
+mod unsupported_multiqueue_error;
+mod unused_image_error;
+mod vm_state_snapshot_error;
+mod write_configuration_error;
 
+pub use backup_failed_error::BackupFailedError;
+pub use booted_guest_error::BootedGuestError;
 pub use command_execution_failed_error::CommandExecutionFailedError;
+pub use duplicate_guest_error::DuplicateGuestError;
 pub use duplicate_snapshot_error::DuplicateSnapshotError;
+pub use guest_agent_command_error::GuestAgentCommandError;
+pub use guest_command_failed_error::GuestCommandFailedError;
+pub use image_too_large_error::ImageTooLargeError;
+pub use invalid_copy_paths_error::InvalidCopyPathsError;
+pub use invalid_network_address_error::InvalidNetworkAddressError;
+pub use invalid_tap_name_error::InvalidTapNameError;
+pub use leftover_image_error::LeftoverImageError;
+pub use missing_base_backup_error::MissingBaseBackupError;
 pub use missing_configuration_error::MissingConfigurationError;
+pub use missing_guest_agent_socket_error::MissingGuestAgentSocketError;
+pub use missing_guest_path_error::MissingGuestPathError;
+pub use missing_nvram_path_error::MissingNvramPathError;
+pub use missing_qmp_socket_error::MissingQmpSocketError;
+pub use missing_serial_log_error::MissingSerialLogError;
+pub use missing_serial_socket_error::MissingSerialSocketError;
+pub use missing_tap_error::MissingTapError;
+pub use missing_tpm_state_path_error::MissingTpmStatePathError;
 pub use monitor_command_error::MonitorCommandError;
+pub use network_in_use_error::NetworkInUseError;
+pub use network_interface_conflict_error::NetworkInterfaceConflictError;
+pub use parse_bundle_error::ParseBundleError;
 pub use parse_command_output_error::ParseCommandOutputError;
 pub use parse_configuration_error::ParseConfigurationError;
 pub use parse_stream_error::ParseStreamError;
 pub use process_execution_error::ProcessExecutionError;
 pub use read_configuration_error::ReadConfigurationError;
+pub use read_user_data_error::ReadUserDataError;
+pub use snapshot_loss_error::SnapshotLossError;
+pub use unknown_backup_error::UnknownBackupError;
+pub use unknown_disk_error::UnknownDiskError;
 pub use unknown_guest_error::UnknownGuestError;
 pub use unknown_network_error::UnknownNetworkError;
 pub use unknown_snapshot_error::UnknownSnapshotError;
+pub use unsupported_multiqueue_error::UnsupportedMultiqueueError;
+pub use unused_image_error::UnusedImageError;
+pub use vm_state_snapshot_error::VmStateSnapshotError;
+pub use write_configuration_error::WriteConfigurationError;
@@ -8,6 +8,7 @@ use std::path::Path;
 #[derive(Debug)]
 pub struct MonitorCommandError {
     command: String,
+    message: Option<String>,
     monitor_socket_path: String,
 }
 
@@ -22,9 +23,19 @@ impl MonitorCommandError {
 
         Self {
             command,
+            message: None,
             monitor_socket_path,
         }
     }
+
+    pub fn with_message<T>(self, message: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        let message = Some(message.as_ref().to_string());
+
+        Self { message, ..self }
+    }
 }
 
 impl Display for MonitorCommandError {
@@ -35,7 +46,12 @@ impl Display for MonitorCommandError {
         write!(
             f,
             "failed to issue command '{command}' to monitor socket '{monitor_socket_path}'"
-        )
+        )?;
+        if let Some(message) = &self.message {
+            write!(f, ": {message}")?;
+        }
+
+        Ok(())
     }
 }
 
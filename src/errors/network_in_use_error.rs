@@ -0,0 +1,41 @@
+use colored::*;
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result;
+
+#[derive(Debug)]
+pub struct NetworkInUseError {
+    guest_id: String,
+    network_id: String,
+}
+
+impl NetworkInUseError {
+    pub fn new<T, U>(network_id: T, guest_id: U) -> Self
+    where
+        T: AsRef<str>,
+        U: AsRef<str>,
+    {
+        let guest_id = guest_id.as_ref().to_string();
+        let network_id = network_id.as_ref().to_string();
+
+        Self {
+            guest_id,
+            network_id,
+        }
+    }
+}
+
+impl Display for NetworkInUseError {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let guest_id = self.guest_id.yellow();
+        let network_id = self.network_id.yellow();
+
+        write!(
+            f,
+            "network '{network_id}' is used by running guest '{guest_id}'"
+        )
+    }
+}
+
+impl Error for NetworkInUseError {}
@@ -0,0 +1,46 @@
+use colored::*;
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result;
+
+#[derive(Debug)]
+pub struct NetworkInterfaceConflictError {
+    guest_id: String,
+    other_guest_id: String,
+    value: String,
+}
+
+impl NetworkInterfaceConflictError {
+    pub fn new<T, U, V>(guest_id: T, other_guest_id: U, value: V) -> Self
+    where
+        T: AsRef<str>,
+        U: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let guest_id = guest_id.as_ref().to_string();
+        let other_guest_id = other_guest_id.as_ref().to_string();
+        let value = value.as_ref().to_string();
+
+        Self {
+            guest_id,
+            other_guest_id,
+            value,
+        }
+    }
+}
+
+impl Display for NetworkInterfaceConflictError {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let guest_id = self.guest_id.yellow();
+        let other_guest_id = self.other_guest_id.yellow();
+        let value = self.value.yellow();
+
+        write!(
+            f,
+            "network interface '{value}' of guest '{guest_id}' is also used by guest '{other_guest_id}'"
+        )
+    }
+}
+
+impl Error for NetworkInterfaceConflictError {}
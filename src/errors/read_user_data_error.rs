@@ -0,0 +1,32 @@
+use colored::*;
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result;
+use std::path::Path;
+
+#[derive(Debug)]
+pub struct ReadUserDataError {
+    path: String,
+}
+
+impl ReadUserDataError {
+    pub fn new<T>(path: T) -> Self
+    where
+        T: AsRef<Path>,
+    {
+        let path = path.as_ref().display().to_string();
+
+        Self { path }
+    }
+}
+
+impl Display for ReadUserDataError {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let path = self.path.yellow();
+
+        write!(f, "failed to read cloud-init user data from '{path}'")
+    }
+}
+
+impl Error for ReadUserDataError {}
@@ -0,0 +1,38 @@
+use colored::*;
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result;
+
+#[derive(Debug)]
+pub struct UnknownBackupError {
+    backup_id: String,
+    guest_id: String,
+}
+
+impl UnknownBackupError {
+    pub fn new<T, U>(guest_id: T, backup_id: U) -> Self
+    where
+        T: AsRef<str>,
+        U: AsRef<str>,
+    {
+        let backup_id = backup_id.as_ref().to_string();
+        let guest_id = guest_id.as_ref().to_string();
+
+        Self {
+            backup_id,
+            guest_id,
+        }
+    }
+}
+
+impl Display for UnknownBackupError {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let backup_id = self.backup_id.yellow();
+        let guest_id = self.guest_id.yellow();
+
+        write!(f, "unknown backup '{backup_id}' for guest '{guest_id}'")
+    }
+}
+
+impl Error for UnknownBackupError {}
@@ -0,0 +1,35 @@
+use colored::*;
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result;
+
+#[derive(Debug)]
+pub struct UnknownDiskError {
+    disk_id: String,
+    guest_id: String,
+}
+
+impl UnknownDiskError {
+    pub fn new<T, U>(guest_id: T, disk_id: U) -> Self
+    where
+        T: AsRef<str>,
+        U: AsRef<str>,
+    {
+        let disk_id = disk_id.as_ref().to_string();
+        let guest_id = guest_id.as_ref().to_string();
+
+        Self { disk_id, guest_id }
+    }
+}
+
+impl Display for UnknownDiskError {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let disk_id = self.disk_id.yellow();
+        let guest_id = self.guest_id.yellow();
+
+        write!(f, "unknown disk '{disk_id}' for guest '{guest_id}'")
+    }
+}
+
+impl Error for UnknownDiskError {}
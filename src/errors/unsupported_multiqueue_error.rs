@@ -0,0 +1,38 @@
+use colored::*;
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result;
+
+#[derive(Debug)]
+pub struct UnsupportedMultiqueueError {
+    guest_id: String,
+    model: String,
+}
+
+impl UnsupportedMultiqueueError {
+    pub fn new<T, U>(guest_id: T, model: U) -> Self
+    where
+        T: AsRef<str>,
+        U: AsRef<str>,
+    {
+        let guest_id = guest_id.as_ref().to_string();
+        let model = model.as_ref().to_string();
+
+        Self { guest_id, model }
+    }
+}
+
+impl Display for UnsupportedMultiqueueError {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let guest_id = self.guest_id.yellow();
+        let model = self.model.yellow();
+
+        write!(
+            f,
+            "network interface model '{model}' of guest '{guest_id}' does not support queues"
+        )
+    }
+}
+
+impl Error for UnsupportedMultiqueueError {}
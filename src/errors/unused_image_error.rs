@@ -0,0 +1,42 @@
+use colored::*;
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result;
+use std::path::Path;
+
+#[derive(Debug)]
+pub struct UnusedImageError {
+    guest_id: String,
+    image_path: String,
+}
+
+impl UnusedImageError {
+    pub fn new<T, U>(guest_id: T, image_path: U) -> Self
+    where
+        T: AsRef<str>,
+        U: AsRef<Path>,
+    {
+        let guest_id = guest_id.as_ref().to_string();
+        let image_path = image_path.as_ref().display().to_string();
+
+        Self {
+            guest_id,
+            image_path,
+        }
+    }
+}
+
+impl Display for UnusedImageError {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let guest_id = self.guest_id.yellow();
+        let image_path = self.image_path.yellow();
+
+        write!(
+            f,
+            "image '{image_path}' is only written to a missing first disk, which guest '{guest_id}' does not have"
+        )
+    }
+}
+
+impl Error for UnusedImageError {}
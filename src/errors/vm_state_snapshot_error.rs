@@ -0,0 +1,41 @@
+use colored::*;
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result;
+
+#[derive(Debug)]
+pub struct VmStateSnapshotError {
+    guest_id: String,
+    snapshot_id: String,
+}
+
+impl VmStateSnapshotError {
+    pub fn new<T, U>(guest_id: T, snapshot_id: U) -> Self
+    where
+        T: AsRef<str>,
+        U: AsRef<str>,
+    {
+        let guest_id = guest_id.as_ref().to_string();
+        let snapshot_id = snapshot_id.as_ref().to_string();
+
+        Self {
+            guest_id,
+            snapshot_id,
+        }
+    }
+}
+
+impl Display for VmStateSnapshotError {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let guest_id = self.guest_id.yellow();
+        let snapshot_id = self.snapshot_id.yellow();
+
+        write!(
+            f,
+            "snapshot '{snapshot_id}' of guest '{guest_id}' contains VM state, which can not be kept"
+        )
+    }
+}
+
+impl Error for VmStateSnapshotError {}
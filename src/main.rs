@@ -36,12 +36,28 @@ enum Command {
         guest_id: String,
     },
 
+    #[clap(about = "Show what the guest agent reports from inside the guest")]
+    #[clap(alias = "guest-info")]
+    ShowGuestInfo {
+        #[clap(help = "Guest ID")]
+        guest_id: String,
+    },
+
+    #[clap(about = "Show disk usage of all guests and storage paths")]
+    #[clap(alias = "df")]
+    ShowDiskUsage,
+
     #[clap(about = "Initialize guest")]
     #[clap(alias = "init")]
     #[clap(alias = "init-guest")]
     InitializeGuest {
         #[clap(help = "Guest ID")]
         guest_id: String,
+
+        #[clap(help = "Create the first disk from image at specified path")]
+        #[clap(long = "from-image")]
+        #[clap(value_name = "IMAGE_PATH")]
+        image_path: Option<PathBuf>,
     },
 
     #[clap(about = "Start guest")]
@@ -93,6 +109,17 @@ enum Command {
         guest_id: String,
     },
 
+    #[clap(about = "Attach to guest serial console, press Ctrl-] to detach")]
+    #[clap(alias = "console")]
+    ConnectToConsole {
+        #[clap(help = "Guest ID")]
+        guest_id: String,
+
+        #[clap(help = "Follow the serial log instead")]
+        #[clap(long)]
+        log: bool,
+    },
+
     #[clap(about = "Establish SSH connection to guest")]
     #[clap(alias = "ssh")]
     ConnectToGuest {
@@ -105,6 +132,47 @@ enum Command {
         args: Vec<String>,
     },
 
+    #[clap(about = "Copy files between guest and local machine")]
+    #[clap(alias = "copy")]
+    CopyFiles {
+        #[clap(help = "Source path, guest paths look like GUEST:PATH")]
+        source: String,
+
+        #[clap(help = "Target path, guest paths look like GUEST:PATH")]
+        target: String,
+
+        #[clap(help = "Copy directories recursively")]
+        #[clap(long)]
+        #[clap(short)]
+        recursive: bool,
+
+        #[clap(help = "Show progress")]
+        #[clap(long)]
+        progress: bool,
+    },
+
+    #[clap(about = "Execute command in guests")]
+    #[clap(alias = "exec")]
+    ExecuteInGuests {
+        #[clap(help = "Guest IDs")]
+        #[clap(required = true)]
+        #[clap(value_name = "GUEST_ID")]
+        guest_ids: Vec<String>,
+
+        #[clap(help = "Seconds to wait for the command to finish")]
+        #[clap(long)]
+        timeout: Option<u64>,
+
+        #[clap(help = "Print results as JSON")]
+        #[clap(long)]
+        json: bool,
+
+        #[clap(help = "Command to execute")]
+        #[clap(last = true)]
+        #[clap(required = true)]
+        args: Vec<String>,
+    },
+
     #[clap(about = "List snapshots")]
     ListSnapshots {
         #[clap(help = "Guest ID")]
@@ -152,6 +220,141 @@ enum Command {
         snapshot_id: String,
     },
 
+    #[clap(about = "Check guest disks for consistency")]
+    CheckDisks {
+        #[clap(help = "Guest ID")]
+        guest_id: String,
+
+        #[clap(help = "Repair leaked clusters or all inconsistencies")]
+        #[clap(long)]
+        #[clap(value_name = "MODE")]
+        #[clap(value_parser = ["leaks", "all"])]
+        repair: Option<String>,
+    },
+
+    #[clap(about = "Compact guest disks")]
+    CompactDisk {
+        #[clap(help = "Guest ID")]
+        guest_id: String,
+
+        #[clap(help = "Disk label, all disks are compacted if omitted")]
+        disk_id: Option<String>,
+
+        #[clap(help = "Compress the compacted image")]
+        #[clap(long)]
+        compress: bool,
+
+        #[clap(help = "Keep internal snapshots, recreated with the current date")]
+        #[clap(conflicts_with_all = ["compress", "disk_id"])]
+        #[clap(long)]
+        keep_snapshots: bool,
+    },
+
+    #[clap(about = "Export guest into a bundle")]
+    #[clap(alias = "export")]
+    ExportGuest {
+        #[clap(help = "Guest ID")]
+        guest_id: String,
+
+        #[clap(help = "Path to the bundle on the host")]
+        #[clap(value_name = "FILE")]
+        path: PathBuf,
+    },
+
+    #[clap(about = "Import guest from a bundle")]
+    #[clap(alias = "import")]
+    ImportGuest {
+        #[clap(help = "Path to the bundle on the host")]
+        #[clap(value_name = "FILE")]
+        path: PathBuf,
+
+        #[clap(help = "Import the guest under a different ID")]
+        #[clap(long = "as")]
+        #[clap(value_name = "NEW_ID")]
+        guest_id: Option<String>,
+    },
+
+    #[clap(about = "Back up guest disks")]
+    #[clap(alias = "backup")]
+    BackupGuest {
+        #[clap(help = "Guest ID")]
+        guest_id: String,
+
+        #[clap(help = "Path to the backup directory on the host")]
+        #[clap(long = "to")]
+        #[clap(value_name = "DIR")]
+        path: PathBuf,
+
+        #[clap(help = "Copy only changes since the last backup")]
+        #[clap(long = "incremental")]
+        incremental: bool,
+
+        #[clap(help = "Number of most recent backups to keep")]
+        #[clap(long = "keep")]
+        #[clap(value_name = "COUNT")]
+        #[clap(value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+        keep: Option<usize>,
+    },
+
+    #[clap(about = "List guest backups")]
+    #[clap(alias = "backups")]
+    ListBackups {
+        #[clap(help = "Guest ID")]
+        guest_id: String,
+
+        #[clap(help = "Path to the backup directory on the host")]
+        #[clap(long = "from")]
+        #[clap(value_name = "DIR")]
+        path: PathBuf,
+    },
+
+    #[clap(about = "Restore guest disks from a backup")]
+    RestoreGuest {
+        #[clap(help = "Guest ID")]
+        guest_id: String,
+
+        #[clap(help = "Backup ID")]
+        backup_id: String,
+
+        #[clap(help = "Path to the backup directory on the host")]
+        #[clap(long = "from")]
+        #[clap(value_name = "DIR")]
+        path: PathBuf,
+
+        #[clap(help = "Restore even if the snapshots of the guest are dropped")]
+        #[clap(long)]
+        force: bool,
+    },
+
+    #[clap(about = "List all networks")]
+    #[clap(alias = "networks")]
+    ListNetworks,
+
+    #[clap(about = "Show network details")]
+    #[clap(alias = "network")]
+    ShowNetwork {
+        #[clap(help = "Network ID")]
+        network_id: String,
+    },
+
+    #[clap(about = "Create network bridge on the host")]
+    CreateNetwork {
+        #[clap(help = "Network ID")]
+        network_id: String,
+    },
+
+    #[clap(about = "Delete network bridge from the host")]
+    DeleteNetwork {
+        #[clap(help = "Network ID")]
+        network_id: String,
+    },
+
+    #[clap(about = "Reconcile guest taps and firewall rules with the configuration")]
+    RepairGuest {
+        #[clap(help = "Guest ID")]
+        guest_id: String,
+    },
+
     #[clap(about = "Print version information")]
     Version,
 }
@@ -202,7 +405,12 @@ fn run(options: Options) -> Result<()> {
     match options.command {
         Command::ListGuests => app.list_guests()?,
         Command::ShowGuestDetails { guest_id } => app.show_guest_details(guest_id)?,
-        Command::InitializeGuest { guest_id } => app.initialize_guest(guest_id)?,
+        Command::ShowGuestInfo { guest_id } => app.show_guest_info(guest_id)?,
+        Command::ShowDiskUsage => app.show_disk_usage()?,
+        Command::InitializeGuest {
+            guest_id,
+            image_path,
+        } => app.initialize_guest(guest_id, image_path)?,
         Command::StartGuest {
             boot_from_cdrom,
             boot_from_network,
@@ -222,7 +430,20 @@ fn run(options: Options) -> Result<()> {
             force,
         } => app.stop_guest(guest_id, wait, force)?,
         Command::WaitForGuestToShutdown { guest_id } => app.wait_for_guest_to_shutdown(guest_id)?,
+        Command::ConnectToConsole { guest_id, log } => app.connect_to_console(guest_id, log)?,
         Command::ConnectToGuest { guest_id, args } => app.connect_to_guest(guest_id, args)?,
+        Command::CopyFiles {
+            progress,
+            recursive,
+            source,
+            target,
+        } => app.copy_files(source, target, recursive, progress)?,
+        Command::ExecuteInGuests {
+            args,
+            guest_ids,
+            json,
+            timeout,
+        } => app.execute_in_guests(guest_ids, args, timeout, json)?,
         Command::ListSnapshots { guest_id } => app.list_snapshots(guest_id)?,
         Command::CreateSnapshot {
             guest_id,
@@ -240,6 +461,33 @@ fn run(options: Options) -> Result<()> {
             guest_id,
             snapshot_id,
         } => app.check_snapshot(guest_id, snapshot_id)?,
+        Command::CheckDisks { guest_id, repair } => app.check_disks(guest_id, repair)?,
+        Command::CompactDisk {
+            guest_id,
+            disk_id,
+            compress,
+            keep_snapshots,
+        } => app.compact_disk(guest_id, disk_id, compress, keep_snapshots)?,
+        Command::ExportGuest { guest_id, path } => app.export_guest(guest_id, path)?,
+        Command::ImportGuest { path, guest_id } => app.import_guest(path, guest_id)?,
+        Command::BackupGuest {
+            guest_id,
+            path,
+            incremental,
+            keep,
+        } => app.backup_guest(guest_id, path, incremental, keep)?,
+        Command::ListBackups { guest_id, path } => app.list_backups(guest_id, path)?,
+        Command::RestoreGuest {
+            guest_id,
+            backup_id,
+            path,
+            force,
+        } => app.restore_guest(guest_id, backup_id, path, force)?,
+        Command::ListNetworks => app.list_networks()?,
+        Command::ShowNetwork { network_id } => app.show_network(network_id)?,
+        Command::CreateNetwork { network_id } => app.create_network(network_id)?,
+        Command::DeleteNetwork { network_id } => app.delete_network(network_id)?,
+        Command::RepairGuest { guest_id } => app.repair_guest(guest_id)?,
         Command::Version => unreachable!(),
     }
 
@@ -21,9 +21,11 @@ fn cli_tests() {
     t.case("tests/cmd/apply_snapshot/common_snapshots_for_multiple_disks.toml");
     t.case("tests/cmd/apply_snapshot/happy_path.toml");
     t.case("tests/cmd/apply_snapshot/happy_path_with_apply_alias.toml");
+    t.case("tests/cmd/apply_snapshot/happy_path_with_backup_bitmap.toml");
     t.case("tests/cmd/apply_snapshot/happy_path_with_restore_alias.toml");
     t.case("tests/cmd/apply_snapshot/happy_path_with_revert_alias.toml");
     t.case("tests/cmd/apply_snapshot/happy_path_with_switch_alias.toml");
+    t.case("tests/cmd/apply_snapshot/happy_path_with_uefi.toml");
     t.case("tests/cmd/apply_snapshot/help.toml");
     t.case("tests/cmd/apply_snapshot/list_snapshots_failure.toml");
     t.case("tests/cmd/apply_snapshot/more_than_two_arguments.toml");
@@ -33,6 +35,45 @@ fn cli_tests() {
     t.case("tests/cmd/apply_snapshot/unknown_guest.toml");
     t.case("tests/cmd/apply_snapshot/unknown_snapshot_failure.toml");
 
+    t.case("tests/cmd/backup_failed_error/error.toml");
+
+    t.case("tests/cmd/backup_guest/backup_job_failure.toml");
+    t.case("tests/cmd/backup_guest/booted_guest_without_qmp_socket.toml");
+    t.case("tests/cmd/backup_guest/check_failure.toml");
+    t.case("tests/cmd/backup_guest/convert_failure.toml");
+    t.case("tests/cmd/backup_guest/happy_path.toml");
+    t.case("tests/cmd/backup_guest/happy_path_for_booted_guest_with_guest_agent.toml");
+    t.case("tests/cmd/backup_guest/happy_path_for_booted_guest_with_qmp.toml");
+    t.case("tests/cmd/backup_guest/happy_path_incremental.toml");
+    t.case("tests/cmd/backup_guest/happy_path_incremental_for_booted_guest.toml");
+    t.case("tests/cmd/backup_guest/happy_path_with_backup_alias.toml");
+    t.case("tests/cmd/backup_guest/happy_path_with_retention.toml");
+    t.case("tests/cmd/backup_guest/happy_path_with_retention_keeping_chain.toml");
+    t.case("tests/cmd/backup_guest/happy_path_with_uefi.toml");
+    t.case("tests/cmd/backup_guest/help.toml");
+    t.case("tests/cmd/backup_guest/incremental_convert_failure.toml");
+    t.case("tests/cmd/backup_guest/incremental_for_booted_guest_without_qmp_socket.toml");
+    t.case("tests/cmd/backup_guest/incremental_without_base_backup.toml");
+    t.case("tests/cmd/backup_guest/keep_zero.toml");
+    t.case("tests/cmd/backup_guest/missing_directory.toml");
+    t.case("tests/cmd/backup_guest/no_arguments.toml");
+    t.case("tests/cmd/backup_guest/thaw_failure.toml");
+    t.case("tests/cmd/backup_guest/transaction_failure.toml");
+    t.case("tests/cmd/backup_guest/unknown_guest.toml");
+
+    t.case("tests/cmd/booted_guest_error/error.toml");
+
+    t.case("tests/cmd/check_disks/check_failure.toml");
+    t.case("tests/cmd/check_disks/happy_path.toml");
+    t.case("tests/cmd/check_disks/happy_path_with_errors.toml");
+    t.case("tests/cmd/check_disks/happy_path_with_repair.toml");
+    t.case("tests/cmd/check_disks/help.toml");
+    t.case("tests/cmd/check_disks/invalid_repair_mode.toml");
+    t.case("tests/cmd/check_disks/more_than_one_argument.toml");
+    t.case("tests/cmd/check_disks/no_arguments.toml");
+    t.case("tests/cmd/check_disks/repair_of_booted_guest_failure.toml");
+    t.case("tests/cmd/check_disks/unknown_guest.toml");
+
     t.case("tests/cmd/check_snapshot/happy_negative_path.toml");
     t.case("tests/cmd/check_snapshot/happy_path.toml");
     t.case("tests/cmd/check_snapshot/help.toml");
@@ -47,25 +88,98 @@ fn cli_tests() {
     t.case("tests/cmd/command_execution_failed_error/error_without_stdout.toml");
     t.case("tests/cmd/command_execution_failed_error/error_without_streams.toml");
 
+    t.case("tests/cmd/compact_disk/booted_guest_failure.toml");
+    t.case("tests/cmd/compact_disk/conflicting_flags.toml");
+    t.case("tests/cmd/compact_disk/convert_failure.toml");
+    t.case("tests/cmd/compact_disk/happy_path.toml");
+    t.case("tests/cmd/compact_disk/happy_path_keeping_originals.toml");
+    t.case("tests/cmd/compact_disk/happy_path_with_compression.toml");
+    t.case("tests/cmd/compact_disk/happy_path_with_snapshots.toml");
+    t.case("tests/cmd/compact_disk/help.toml");
+    t.case("tests/cmd/compact_disk/keep_snapshots_with_disk.toml");
+    t.case("tests/cmd/compact_disk/more_than_two_arguments.toml");
+    t.case("tests/cmd/compact_disk/no_arguments.toml");
+    t.case("tests/cmd/compact_disk/snapshot_replay_failure.toml");
+    t.case("tests/cmd/compact_disk/unknown_disk.toml");
+    t.case("tests/cmd/compact_disk/unknown_guest.toml");
+
+    t.case("tests/cmd/connect_to_console/connection_failure.toml");
+    t.case("tests/cmd/connect_to_console/happy_path.toml");
+    t.case("tests/cmd/connect_to_console/happy_path_with_console_alias.toml");
+    t.case("tests/cmd/connect_to_console/happy_path_with_log.toml");
+    t.case("tests/cmd/connect_to_console/help.toml");
+    t.case("tests/cmd/connect_to_console/missing_serial_log_failure.toml");
+    t.case("tests/cmd/connect_to_console/missing_serial_socket_failure.toml");
+    t.case("tests/cmd/connect_to_console/more_than_one_argument.toml");
+    t.case("tests/cmd/connect_to_console/no_arguments.toml");
+    t.case("tests/cmd/connect_to_console/unknown_guest.toml");
+
     t.case("tests/cmd/connect_to_guest/happy_path.toml");
+    t.case("tests/cmd/connect_to_guest/happy_path_with_ssh_settings.toml");
+    t.case("tests/cmd/connect_to_guest/happy_path_without_guest_ssh_settings.toml");
     t.case("tests/cmd/connect_to_guest/help.toml");
     t.case("tests/cmd/connect_to_guest/more_than_one_argument.toml");
     t.case("tests/cmd/connect_to_guest/no_arguments.toml");
     t.case("tests/cmd/connect_to_guest/unknown_guest.toml");
 
+    t.case("tests/cmd/copy_files/copy_failure.toml");
+    t.case("tests/cmd/copy_files/happy_path_download.toml");
+    t.case("tests/cmd/copy_files/happy_path_recursive.toml");
+    t.case("tests/cmd/copy_files/happy_path_upload.toml");
+    t.case("tests/cmd/copy_files/happy_path_with_copy_alias.toml");
+    t.case("tests/cmd/copy_files/happy_path_with_progress.toml");
+    t.case("tests/cmd/copy_files/help.toml");
+    t.case("tests/cmd/copy_files/more_than_two_arguments.toml");
+    t.case("tests/cmd/copy_files/no_arguments.toml");
+    t.case("tests/cmd/copy_files/one_argument.toml");
+    t.case("tests/cmd/copy_files/two_guest_paths.toml");
+    t.case("tests/cmd/copy_files/two_local_paths.toml");
+    t.case("tests/cmd/copy_files/unknown_guest.toml");
+
+    t.case("tests/cmd/create_network/bridge_creation_failure.toml");
+    t.case("tests/cmd/create_network/dhcp_without_address_failure.toml");
+    t.case("tests/cmd/create_network/happy_path.toml");
+    t.case("tests/cmd/create_network/happy_path_with_changed_dhcp_configuration.toml");
+    t.case("tests/cmd/create_network/happy_path_with_dhcp.toml");
+    t.case("tests/cmd/create_network/happy_path_with_isolated_mode.toml");
+    t.case("tests/cmd/create_network/happy_path_with_jumbo_frames.toml");
+    t.case("tests/cmd/create_network/happy_path_with_nat_mode.toml");
+    t.case("tests/cmd/create_network/happy_path_without_address.toml");
+    t.case("tests/cmd/create_network/help.toml");
+    t.case("tests/cmd/create_network/more_than_one_argument.toml");
+    t.case("tests/cmd/create_network/nat_mode_without_address_failure.toml");
+    t.case("tests/cmd/create_network/no_arguments.toml");
+    t.case("tests/cmd/create_network/noop_when_bridge_exists.toml");
+    t.case("tests/cmd/create_network/noop_when_dhcp_is_up_to_date.toml");
+    t.case("tests/cmd/create_network/unknown_network.toml");
+
     t.case("tests/cmd/create_snapshot/common_snapshots_for_multiple_disks.toml");
     t.case("tests/cmd/create_snapshot/create_snapshot_failure.toml");
     t.case("tests/cmd/create_snapshot/happy_path.toml");
     t.case("tests/cmd/create_snapshot/happy_path_with_snapshot_alias.toml");
+    t.case("tests/cmd/create_snapshot/happy_path_with_uefi.toml");
     t.case("tests/cmd/create_snapshot/help.toml");
     t.case("tests/cmd/create_snapshot/list_snapshots_failure.toml");
     t.case("tests/cmd/create_snapshot/more_than_two_arguments.toml");
     t.case("tests/cmd/create_snapshot/no_arguments.toml");
+    t.case("tests/cmd/create_snapshot/nvram_snapshot_already_exists_failure.toml");
     t.case("tests/cmd/create_snapshot/one_argument.toml");
     t.case("tests/cmd/create_snapshot/snapshot_already_exists_failure.toml");
     t.case("tests/cmd/create_snapshot/unknown_guest.toml");
 
+    t.case("tests/cmd/delete_network/happy_path.toml");
+    t.case("tests/cmd/delete_network/happy_path_with_dhcp.toml");
+    t.case("tests/cmd/delete_network/happy_path_with_nat_mode.toml");
+    t.case("tests/cmd/delete_network/help.toml");
+    t.case("tests/cmd/delete_network/more_than_one_argument.toml");
+    t.case("tests/cmd/delete_network/network_in_use_failure.toml");
+    t.case("tests/cmd/delete_network/no_arguments.toml");
+    t.case("tests/cmd/delete_network/noop_when_bridge_is_missing.toml");
+    t.case("tests/cmd/delete_network/unknown_network.toml");
+
     t.case("tests/cmd/delete_snapshot/happy_path.toml");
+    t.case("tests/cmd/delete_snapshot/happy_path_with_uefi.toml");
+    t.case("tests/cmd/delete_snapshot/happy_path_with_uefi_when_nvram_lacks_snapshot.toml");
     t.case("tests/cmd/delete_snapshot/help.toml");
     t.case("tests/cmd/delete_snapshot/more_than_two_arguments.toml");
     t.case("tests/cmd/delete_snapshot/multiple_disks.toml");
@@ -74,29 +188,112 @@ fn cli_tests() {
     t.case("tests/cmd/delete_snapshot/snapshot_removal_failure.toml");
     t.case("tests/cmd/delete_snapshot/unknown_guest.toml");
 
+    t.case("tests/cmd/duplicate_guest_error/error.toml");
+
     t.case("tests/cmd/duplicate_snapshot_error/error.toml");
 
+    t.case("tests/cmd/execute_in_guests/command_failure.toml");
+    t.case("tests/cmd/execute_in_guests/command_failure_with_json.toml");
+    t.case("tests/cmd/execute_in_guests/command_failure_with_multiple_guests.toml");
+    t.case("tests/cmd/execute_in_guests/connection_lost_after_probe.toml");
+    t.case("tests/cmd/execute_in_guests/guest_agent_timeout.toml");
+    t.case("tests/cmd/execute_in_guests/happy_path.toml");
+    t.case("tests/cmd/execute_in_guests/happy_path_via_guest_agent.toml");
+    t.case("tests/cmd/execute_in_guests/happy_path_with_exec_alias.toml");
+    t.case("tests/cmd/execute_in_guests/happy_path_with_json.toml");
+    t.case("tests/cmd/execute_in_guests/happy_path_with_multiple_guests.toml");
+    t.case("tests/cmd/execute_in_guests/happy_path_with_timeout.toml");
+    t.case("tests/cmd/execute_in_guests/happy_path_with_timeout_and_quotes.toml");
+    t.case("tests/cmd/execute_in_guests/help.toml");
+    t.case("tests/cmd/execute_in_guests/local_timeout.toml");
+    t.case("tests/cmd/execute_in_guests/missing_command.toml");
+    t.case("tests/cmd/execute_in_guests/no_arguments.toml");
+    t.case("tests/cmd/execute_in_guests/signal_via_guest_agent.toml");
+    t.case("tests/cmd/execute_in_guests/unknown_guest.toml");
+    t.case("tests/cmd/execute_in_guests/unreachable_guest_without_guest_agent.toml");
+
+    t.case("tests/cmd/export_guest/booted_guest_failure.toml");
+    t.case("tests/cmd/export_guest/happy_path.toml");
+    t.case("tests/cmd/export_guest/happy_path_with_export_alias.toml");
+    t.case("tests/cmd/export_guest/happy_path_with_special_characters.toml");
+    t.case("tests/cmd/export_guest/happy_path_with_uefi.toml");
+    t.case("tests/cmd/export_guest/help.toml");
+    t.case("tests/cmd/export_guest/no_arguments.toml");
+    t.case("tests/cmd/export_guest/one_argument.toml");
+    t.case("tests/cmd/export_guest/tar_failure.toml");
+    t.case("tests/cmd/export_guest/unknown_guest.toml");
+
+    t.case("tests/cmd/guest_agent_command_error/error.toml");
+
+    t.case("tests/cmd/guest_command_failed_error/error.toml");
+
     t.case("tests/cmd/help.toml");
 
+    t.case("tests/cmd/image_too_large_error/error.toml");
+
+    t.case("tests/cmd/import_guest/disk_extraction_failure.toml");
+    t.case("tests/cmd/import_guest/duplicate_guest_failure.toml");
+    t.case("tests/cmd/import_guest/happy_path.toml");
+    t.case("tests/cmd/import_guest/happy_path_with_generated_network_interface_defaults.toml");
+    t.case("tests/cmd/import_guest/happy_path_with_import_alias.toml");
+    t.case("tests/cmd/import_guest/happy_path_with_new_id_and_conflicts.toml");
+    t.case("tests/cmd/import_guest/happy_path_with_new_id_and_long_tap_name.toml");
+    t.case("tests/cmd/import_guest/happy_path_with_uefi.toml");
+    t.case("tests/cmd/import_guest/help.toml");
+    t.case("tests/cmd/import_guest/invalid_bundle_failure.toml");
+    t.case("tests/cmd/import_guest/missing_snapshot_failure.toml");
+    t.case("tests/cmd/import_guest/more_than_one_argument.toml");
+    t.case("tests/cmd/import_guest/no_arguments.toml");
+    t.case("tests/cmd/import_guest/tar_failure.toml");
+    t.case("tests/cmd/import_guest/unknown_network_failure.toml");
+
     t.case("tests/cmd/initialize_guest/disk_creation_failure.toml");
     t.case("tests/cmd/initialize_guest/happy_path.toml");
+    t.case("tests/cmd/initialize_guest/happy_path_with_image.toml");
     t.case("tests/cmd/initialize_guest/happy_path_with_init_alias.toml");
     t.case("tests/cmd/initialize_guest/happy_path_with_init_guest_alias.toml");
     t.case("tests/cmd/initialize_guest/happy_path_with_multiple_disks.toml");
     t.case("tests/cmd/initialize_guest/happy_path_with_multiple_disks_when_some_are_skipped.toml");
+    t.case("tests/cmd/initialize_guest/happy_path_with_uefi.toml");
     t.case("tests/cmd/initialize_guest/help.toml");
+    t.case("tests/cmd/initialize_guest/image_conversion_failure.toml");
+    t.case("tests/cmd/initialize_guest/image_for_existing_disk_failure.toml");
+    t.case("tests/cmd/initialize_guest/image_too_large_failure.toml");
     t.case("tests/cmd/initialize_guest/more_than_one_argument.toml");
     t.case("tests/cmd/initialize_guest/no_arguments.toml");
     t.case("tests/cmd/initialize_guest/noop_when_path_exists.toml");
     t.case("tests/cmd/initialize_guest/snapshot_creation_failure.toml");
     t.case("tests/cmd/initialize_guest/unknown_guest.toml");
 
+    t.case("tests/cmd/invalid_copy_paths_error/error.toml");
+
+    t.case("tests/cmd/invalid_network_address_error/error.toml");
+
+    t.case("tests/cmd/invalid_tap_name_error/error.toml");
+
+    t.case("tests/cmd/leftover_image_error/error.toml");
+
+    t.case("tests/cmd/list_backups/happy_path.toml");
+    t.case("tests/cmd/list_backups/happy_path_with_backups_alias.toml");
+    t.case("tests/cmd/list_backups/help.toml");
+    t.case("tests/cmd/list_backups/missing_directory.toml");
+    t.case("tests/cmd/list_backups/no_arguments.toml");
+    t.case("tests/cmd/list_backups/no_backups.toml");
+    t.case("tests/cmd/list_backups/unknown_guest.toml");
+
     t.case("tests/cmd/list_guests/happy_path.toml");
     t.case("tests/cmd/list_guests/happy_path_with_guests_alias.toml");
     t.case("tests/cmd/list_guests/happy_path_with_list_alias.toml");
+    t.case("tests/cmd/list_guests/happy_path_with_ssh_settings.toml");
     t.case("tests/cmd/list_guests/help.toml");
     t.case("tests/cmd/list_guests/more_than_zero_arguments.toml");
 
+    t.case("tests/cmd/list_networks/happy_path.toml");
+    t.case("tests/cmd/list_networks/happy_path_with_networks_alias.toml");
+    t.case("tests/cmd/list_networks/help.toml");
+    t.case("tests/cmd/list_networks/more_than_zero_arguments.toml");
+    t.case("tests/cmd/list_networks/no_networks.toml");
+
     t.case("tests/cmd/list_snapshots/big_difference_in_snapshot_timestamp_for_multiple_disks.toml");
     t.case("tests/cmd/list_snapshots/common_snapshots_for_multiple_disks.toml");
     t.case("tests/cmd/list_snapshots/happy_path.toml");
@@ -107,19 +304,75 @@ fn cli_tests() {
     t.case("tests/cmd/list_snapshots/no_arguments.toml");
     t.case("tests/cmd/list_snapshots/no_snapshots.toml");
     t.case("tests/cmd/list_snapshots/unknown_guest.toml");
+    t.case("tests/cmd/list_snapshots/vm_state_for_multiple_disks.toml");
+
+    t.case("tests/cmd/missing_base_backup_error/error.toml");
 
     t.case("tests/cmd/missing_configuration_error/error_when_config_path_is_not_specified.toml");
     t.case("tests/cmd/missing_configuration_error/error_when_config_path_is_specified.toml");
 
+    t.case("tests/cmd/missing_guest_agent_socket_error/error.toml");
+
+    t.case("tests/cmd/missing_guest_path_error/error.toml");
+
+    t.case("tests/cmd/missing_nvram_path_error/error.toml");
+
+    t.case("tests/cmd/missing_qmp_socket_error/error.toml");
+
+    t.case("tests/cmd/missing_serial_log_error/error.toml");
+
+    t.case("tests/cmd/missing_serial_socket_error/error.toml");
+
+    t.case("tests/cmd/missing_tap_error/error.toml");
+
+    t.case("tests/cmd/missing_tpm_state_path_error/error.toml");
+
+    t.case("tests/cmd/network_in_use_error/error.toml");
+
+    t.case("tests/cmd/network_interface_conflict_error/error.toml");
+
+    t.case("tests/cmd/parse_bundle_error/error.toml");
+
     t.case("tests/cmd/parse_command_output_error/error.toml");
     t.case("tests/cmd/parse_command_output_error/error_without_stdout.toml");
 
     t.case("tests/cmd/parse_configuration_error/error.toml");
+    t.case("tests/cmd/parse_configuration_error/unknown_port_forward_protocol.toml");
 
     t.case("tests/cmd/print_version.toml");
 
     t.case("tests/cmd/read_configuration_error/error.toml");
 
+    t.case("tests/cmd/read_user_data_error/error.toml");
+
+    t.case("tests/cmd/repair_guest/happy_path.toml");
+    t.case("tests/cmd/repair_guest/happy_path_when_the_guest_is_not_running.toml");
+    t.case("tests/cmd/repair_guest/help.toml");
+    t.case("tests/cmd/repair_guest/missing_tap_failure.toml");
+    t.case("tests/cmd/repair_guest/more_than_one_argument.toml");
+    t.case("tests/cmd/repair_guest/no_arguments.toml");
+    t.case("tests/cmd/repair_guest/unknown_guest.toml");
+
+    t.case("tests/cmd/restore_guest/booted_guest_failure.toml");
+    t.case("tests/cmd/restore_guest/convert_failure.toml");
+    t.case("tests/cmd/restore_guest/happy_path.toml");
+    t.case("tests/cmd/restore_guest/happy_path_with_force.toml");
+    t.case("tests/cmd/restore_guest/happy_path_with_uefi.toml");
+    t.case("tests/cmd/restore_guest/help.toml");
+    t.case("tests/cmd/restore_guest/missing_directory.toml");
+    t.case("tests/cmd/restore_guest/no_arguments.toml");
+    t.case("tests/cmd/restore_guest/one_argument.toml");
+    t.case("tests/cmd/restore_guest/snapshot_loss.toml");
+    t.case("tests/cmd/restore_guest/unknown_backup.toml");
+    t.case("tests/cmd/restore_guest/unknown_guest.toml");
+
+    t.case("tests/cmd/show_disk_usage/df_failure.toml");
+    t.case("tests/cmd/show_disk_usage/happy_path.toml");
+    t.case("tests/cmd/show_disk_usage/happy_path_with_df_alias.toml");
+    t.case("tests/cmd/show_disk_usage/help.toml");
+    t.case("tests/cmd/show_disk_usage/missing_storage_paths.toml");
+    t.case("tests/cmd/show_disk_usage/more_than_zero_arguments.toml");
+
     t.case("tests/cmd/show_guest_details/happy_path.toml");
     t.case("tests/cmd/show_guest_details/happy_path_with_guest_alias.toml");
     t.case("tests/cmd/show_guest_details/happy_path_with_show_alias.toml");
@@ -128,28 +381,76 @@ fn cli_tests() {
     t.case("tests/cmd/show_guest_details/no_arguments.toml");
     t.case("tests/cmd/show_guest_details/unknown_guest.toml");
 
+    t.case("tests/cmd/show_guest_info/happy_path.toml");
+    t.case("tests/cmd/show_guest_info/happy_path_with_guest_info_alias.toml");
+    t.case("tests/cmd/show_guest_info/help.toml");
+    t.case("tests/cmd/show_guest_info/missing_guest_agent_socket_failure.toml");
+    t.case("tests/cmd/show_guest_info/more_than_one_argument.toml");
+    t.case("tests/cmd/show_guest_info/no_arguments.toml");
+    t.case("tests/cmd/show_guest_info/unknown_guest.toml");
+    t.case("tests/cmd/show_guest_info/unresponsive_guest_agent.toml");
+
+    t.case("tests/cmd/show_network/address_failure.toml");
+    t.case("tests/cmd/show_network/happy_path.toml");
+    t.case("tests/cmd/show_network/happy_path_with_network_alias.toml");
+    t.case("tests/cmd/show_network/help.toml");
+    t.case("tests/cmd/show_network/missing_bridge.toml");
+    t.case("tests/cmd/show_network/more_than_one_argument.toml");
+    t.case("tests/cmd/show_network/no_arguments.toml");
+    t.case("tests/cmd/show_network/unknown_network.toml");
+
     t.case("tests/cmd/start_guest/boot_from_more_than_one_source_failure.toml");
+    t.case("tests/cmd/start_guest/genisoimage_failure.toml");
     t.case("tests/cmd/start_guest/guest_start_failure.toml");
+    t.case("tests/cmd/start_guest/guest_start_failure_with_tpm.toml");
+    t.case("tests/cmd/start_guest/happy_path_with_anti_spoofing.toml");
     t.case("tests/cmd/start_guest/happy_path_with_boot_from_cdrom.toml");
     t.case("tests/cmd/start_guest/happy_path_with_boot_from_network.toml");
+    t.case("tests/cmd/start_guest/happy_path_with_cloud_init.toml");
+    t.case("tests/cmd/start_guest/happy_path_with_cloud_init_on_unaddressed_network.toml");
+    t.case("tests/cmd/start_guest/happy_path_with_cloud_init_user_data.toml");
     t.case("tests/cmd/start_guest/happy_path_with_complex_configuration.toml");
+    t.case("tests/cmd/start_guest/happy_path_with_ensure_networks.toml");
+    t.case("tests/cmd/start_guest/happy_path_with_generated_network_names.toml");
+    t.case("tests/cmd/start_guest/happy_path_with_generated_network_names_for_long_guest_id.toml");
+    t.case("tests/cmd/start_guest/happy_path_with_guest_agent.toml");
+    t.case("tests/cmd/start_guest/happy_path_with_network_tuning.toml");
+    t.case("tests/cmd/start_guest/happy_path_with_port_forwards.toml");
+    t.case("tests/cmd/start_guest/happy_path_with_qmp_socket.toml");
+    t.case("tests/cmd/start_guest/happy_path_with_secure_boot.toml");
+    t.case("tests/cmd/start_guest/happy_path_with_serial_console.toml");
+    t.case("tests/cmd/start_guest/happy_path_with_serial_log.toml");
     t.case("tests/cmd/start_guest/happy_path_with_several_cdroms.toml");
+    t.case("tests/cmd/start_guest/happy_path_with_tpm.toml");
+    t.case("tests/cmd/start_guest/happy_path_with_uefi.toml");
+    t.case("tests/cmd/start_guest/happy_path_with_vlan.toml");
     t.case("tests/cmd/start_guest/help.toml");
+    t.case("tests/cmd/start_guest/invalid_tap_name_failure.toml");
     t.case("tests/cmd/start_guest/iproute_failure.toml");
+    t.case("tests/cmd/start_guest/missing_nvram_path_failure.toml");
+    t.case("tests/cmd/start_guest/missing_tpm_state_path_failure.toml");
     t.case("tests/cmd/start_guest/more_than_one_argument.toml");
+    t.case("tests/cmd/start_guest/network_interface_conflict_failure.toml");
     t.case("tests/cmd/start_guest/no_arguments.toml");
     t.case("tests/cmd/start_guest/noop_when_guest_is_already_running.toml");
+    t.case("tests/cmd/start_guest/read_user_data_failure.toml");
     t.case("tests/cmd/start_guest/simple_happy_path.toml");
     t.case("tests/cmd/start_guest/simple_happy_path_with_start_alias.toml");
     t.case("tests/cmd/start_guest/unknown_guest.toml");
 
     t.case("tests/cmd/stop_guest/first_pkill_failure.toml");
     t.case("tests/cmd/stop_guest/happy_path_when_the_guest_is_not_running.toml");
+    t.case("tests/cmd/stop_guest/happy_path_with_anti_spoofing.toml");
     t.case("tests/cmd/stop_guest/happy_path_with_force_flag.toml");
     t.case("tests/cmd/stop_guest/happy_path_with_force_flag_unresponsive.toml");
+    t.case("tests/cmd/stop_guest/happy_path_with_guest_agent.toml");
+    t.case("tests/cmd/stop_guest/happy_path_with_port_forwards.toml");
     t.case("tests/cmd/stop_guest/happy_path_with_soft_shutdown.toml");
     t.case("tests/cmd/stop_guest/happy_path_with_soft_shutdown_timeout.toml");
     t.case("tests/cmd/stop_guest/happy_path_with_soft_shutdown_timeout_and_unresponsive.toml");
+    t.case("tests/cmd/stop_guest/happy_path_with_stale_taps.toml");
+    t.case("tests/cmd/stop_guest/happy_path_with_tpm.toml");
+    t.case("tests/cmd/stop_guest/happy_path_with_unresponsive_guest_agent.toml");
     t.case("tests/cmd/stop_guest/help.toml");
     t.case("tests/cmd/stop_guest/more_than_one_argument.toml");
     t.case("tests/cmd/stop_guest/no_arguments.toml");
@@ -158,12 +459,22 @@ fn cli_tests() {
     t.case("tests/cmd/stop_guest/simple_happy_path_with_stop_alias.toml");
     t.case("tests/cmd/stop_guest/unknown_guest.toml");
 
+    t.case("tests/cmd/unknown_backup_error/error.toml");
+
+    t.case("tests/cmd/unknown_disk_error/error.toml");
+
     t.case("tests/cmd/unknown_guest_error/error.toml");
 
     t.case("tests/cmd/unknown_network_error/error.toml");
 
     t.case("tests/cmd/unknown_snapshot_error/error.toml");
 
+    t.case("tests/cmd/unsupported_multiqueue_error/error.toml");
+
+    t.case("tests/cmd/unused_image_error/error.toml");
+
+    t.case("tests/cmd/vm_state_snapshot_error/error.toml");
+
     t.case("tests/cmd/wait_for_guest_to_shutdown/happy_path_with_wait.toml");
     t.case("tests/cmd/wait_for_guest_to_shutdown/help.toml");
     t.case("tests/cmd/wait_for_guest_to_shutdown/more_than_one_argument.toml");
@@ -171,4 +482,6 @@ fn cli_tests() {
     t.case("tests/cmd/wait_for_guest_to_shutdown/simple_happy_path.toml");
     t.case("tests/cmd/wait_for_guest_to_shutdown/simple_happy_path_with_wait_alias.toml");
     t.case("tests/cmd/wait_for_guest_to_shutdown/unknown_guest.toml");
+
+    t.case("tests/cmd/write_configuration_error/error.toml");
 }